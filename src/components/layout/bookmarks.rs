@@ -0,0 +1,60 @@
+use dioxus::prelude::*;
+use crate::services::{format_references, ReferenceStyle};
+use crate::types::*;
+
+#[component]
+pub fn BookmarksPanel(
+    bookmarks: Vec<Bookmark>,
+    translations: Vec<Translation>,
+    on_open: EventHandler<Bookmark>,
+    on_close: EventHandler<()>,
+) -> Element {
+    rsx! {
+        div {
+            class: "mb-8 rounded-lg border border-primary bg-secondary theme-transition",
+            div {
+                class: "flex items-center justify-between px-4 py-3 border-b border-primary",
+                h2 { class: "text-lg font-semibold text-primary", "Bookmarks" }
+                button {
+                    class: "px-3 py-1 rounded bg-tertiary hover:bg-accent-secondary text-sm",
+                    onclick: move |_| on_close.call(()),
+                    "Close"
+                }
+            }
+            if bookmarks.is_empty() {
+                div { class: "px-4 py-6 text-center text-secondary", "No bookmarks yet" }
+            } else {
+                div {
+                    class: "max-h-[50vh] overflow-y-auto divide-y divide-gray-200 dark:divide-gray-700",
+                    for bookmark in bookmarks.iter().rev() {
+                        button {
+                            key: "{bookmark.id}",
+                            class: "w-full text-left px-4 py-3 hover:bg-tertiary transition-colors flex items-center justify-between",
+                            onclick: {
+                                let bookmark = bookmark.clone();
+                                move |_| on_open.call(bookmark.clone())
+                            },
+                            // Numbered as in the translation it was made in
+                            span {
+                                class: "text-sm font-semibold text-blue-600 dark:text-blue-400",
+                                {
+                                    let point = BibleReference { book_id: bookmark.book_id, chapter: bookmark.chapter, verse: Some(bookmark.verse) };
+                                    format_references(&[VerseRange { start: point.clone(), end: point }], ReferenceStyle::Long)
+                                }
+                            }
+                            span {
+                                class: "text-xs text-secondary",
+                                {
+                                    translations
+                                        .iter()
+                                        .find(|t| t.id == bookmark.translation_id)
+                                        .map_or(bookmark.translation_id.clone(), |t| t.abbreviation.clone())
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
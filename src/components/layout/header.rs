@@ -143,7 +143,7 @@ pub fn Header(
                                 }
                             ),
                             if let Some(book) = &selected_book {
-                                "{book.name} {selected_chapter}"
+                                "{book.display_name()} {selected_chapter}"
                             } else {
                                 "Bible Study App"
                             }
@@ -1,5 +1,9 @@
+pub mod bookmarks;
 pub mod header;
+pub mod search_results;
 pub mod sidebar;
 
+pub use bookmarks::BookmarksPanel;
 pub use header::Header;
+pub use search_results::SearchResultsPanel;
 pub use sidebar::Sidebar;
\ No newline at end of file
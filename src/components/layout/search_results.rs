@@ -0,0 +1,109 @@
+use dioxus::prelude::*;
+use crate::services::{format_references, ReferenceStyle};
+use crate::types::*;
+
+/// Styles offered for copying the hits as references
+const COPY_STYLES: [(&str, ReferenceStyle); 4] = [
+    ("Names", ReferenceStyle::Long),
+    ("Abbreviations", ReferenceStyle::Abbreviation),
+    ("OSIS", ReferenceStyle::Osis),
+    ("Tamil", ReferenceStyle::Tamil),
+];
+
+#[component]
+pub fn SearchResultsPanel(
+    results: SearchResult,
+    /// How "Copy references" writes them until another style is picked
+    reference_style: ReferenceStyle,
+    on_select_result: EventHandler<VerseWithBook>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let mut style = use_signal(|| reference_style);
+    let shown = results.verses.len();
+    let total = results.total_count;
+    // The shown hits as one reference list, e.g. "John 3:16; Romans 8:28, 31"
+    let references = format_references(
+        &results
+            .verses
+            .iter()
+            .map(|hit| {
+                let point = BibleReference { book_id: hit.verse.book_id, chapter: hit.verse.chapter, verse: Some(hit.verse.verse) };
+                VerseRange { start: point.clone(), end: point }
+            })
+            .collect::<Vec<_>>(),
+        *style.read(),
+    );
+
+    rsx! {
+        div {
+            class: "mb-8 rounded-lg border border-primary bg-secondary theme-transition",
+            div {
+                class: "flex items-center justify-between px-4 py-3 border-b border-primary",
+                div {
+                    h2 { class: "text-lg font-semibold text-primary", "Search results for “{results.query}”" }
+                    p {
+                        class: "text-xs text-secondary",
+                        if total > shown {
+                            "Showing {shown} of {total} verses"
+                        } else {
+                            "{total} verses"
+                        }
+                    }
+                }
+                div {
+                    class: "flex gap-2",
+                    if !references.is_empty() {
+                        select {
+                            class: "px-2 py-1 rounded border text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-700 text-gray-900 dark:text-gray-100",
+                            value: "{COPY_STYLES.iter().position(|(_, s)| *s == *style.read()).unwrap_or(0)}",
+                            onchange: move |evt| {
+                                if let Some((_, picked)) = evt.value().parse::<usize>().ok().and_then(|i| COPY_STYLES.get(i)) {
+                                    style.set(*picked);
+                                }
+                            },
+                            for (i, (label, _)) in COPY_STYLES.iter().enumerate() {
+                                option { value: "{i}", "{label}" }
+                            }
+                        }
+                        button {
+                            class: "px-3 py-1 rounded bg-tertiary hover:bg-accent-secondary text-sm",
+                            title: "{references}",
+                            onclick: move |_| {
+                                let text = serde_json::to_string(&references).unwrap_or_default();
+                                document::eval(&format!("navigator.clipboard.writeText({})", text));
+                            },
+                            "Copy references"
+                        }
+                    }
+                    button {
+                        class: "px-3 py-1 rounded bg-tertiary hover:bg-accent-secondary text-sm",
+                        onclick: move |_| on_close.call(()),
+                        "Close"
+                    }
+                }
+            }
+            if results.verses.is_empty() {
+                div { class: "px-4 py-6 text-center text-secondary", "No verses found" }
+            } else {
+                div {
+                    class: "max-h-[50vh] overflow-y-auto divide-y divide-gray-200 dark:divide-gray-700",
+                    for hit in results.verses.iter() {
+                        button {
+                            key: "{hit.verse.id}",
+                            class: "w-full text-left px-4 py-3 hover:bg-tertiary transition-colors",
+                            onclick: {
+                                let hit = hit.clone();
+                                move |_| on_select_result.call(hit.clone())
+                            },
+                            div {
+                                class: "text-xs font-semibold text-blue-600 dark:text-blue-400 mb-1",
+                                "{hit.book_name} {hit.verse.chapter}:{hit.verse.verse}"
+                            }
+                            p { class: "text-sm text-primary leading-relaxed", "{hit.verse.text}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -18,9 +18,16 @@ pub fn Sidebar(
     // For now reuse settings button to open translations modal from parent
     on_toggle_sidebar: EventHandler<()>,
 ) -> Element {
-    // Separate books by testament
-    let old_testament_books: Vec<&Book> = books.iter().filter(|book| book.testament == Testament::OT).collect();
-    let new_testament_books: Vec<&Book> = books.iter().filter(|book| book.testament == Testament::NT).collect();
+    // Separate books by testament, leaving out testaments the translation doesn't cover
+    let coverage = selected_translation.as_ref().and_then(|t| t.coverage);
+    let section = |testament: Testament| -> Vec<&Book> {
+        if coverage.is_some_and(|c| !c.includes(&testament)) {
+            return Vec::new();
+        }
+        books.iter().filter(|book| book.testament == testament).collect()
+    };
+    let old_testament_books = section(Testament::OT);
+    let new_testament_books = section(Testament::NT);
 
     rsx! {
         // Clean vertical sidebar
@@ -109,7 +116,7 @@ pub fn Sidebar(
                                     },
                                     span { 
                                         class: "font-medium",
-                                        "{book.name}" 
+                                        "{book.display_name()}" 
                                     }
                                     span {
                                         class: "text-xs text-gray-500 dark:text-gray-400",
@@ -156,7 +163,7 @@ pub fn Sidebar(
                                     },
                                     span { 
                                         class: "font-medium",
-                                        "{book.name}" 
+                                        "{book.display_name()}" 
                                     }
                                     span {
                                         class: "text-xs text-gray-500 dark:text-gray-400",
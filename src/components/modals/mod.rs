@@ -1,17 +1,68 @@
 use dioxus::prelude::*;
-use crate::types::Translation;
-use crate::services::BibleService;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use crate::error::BibleError;
+use crate::types::{Translation, TranslationStatus};
+use crate::services::settings::Settings;
+use crate::services::{DownloadProgress, DownloadQueue, QueueItemState, QueueSnapshot, SharedBibleService};
 
 #[component]
 pub fn TranslationsModal(
     is_open: bool,
     translations: Vec<Translation>,
     on_close: EventHandler<()>,
+    on_translation_imported: EventHandler<Translation>,
+    on_translation_changed: EventHandler<String>,
+    on_index_reloaded: EventHandler<Vec<Translation>>,
 ) -> Element {
     if !is_open { return rsx! { }; }
 
     let mut search_query = use_signal(|| String::new());
     let mut selected_language = use_signal(|| String::new());
+    let queue = use_context::<DownloadQueue>();
+    // Rows and the batch summary re-render as queued items move along
+    let queue_state = use_queue_snapshot();
+    let service = use_context::<CopyValue<SharedBibleService>>();
+
+    // Translations with files on disk, as the rows count them; rechecked whenever the list
+    // changes or another queued download completes
+    let mut on_disk = use_signal(HashSet::<String>::new);
+    let finished_count = use_memo(move || queue_state.read().values().filter(|s| matches!(s, QueueItemState::Done)).count());
+    use_effect(use_reactive!(|translations| {
+        let _ = finished_count.read();
+        spawn(async move {
+            let svc = service.cloned().lock_owned().await;
+            on_disk.set(
+                translations
+                    .iter()
+                    .filter(|t| matches!(svc.translation_status(&t.id), TranslationStatus::Downloaded | TranslationStatus::Error))
+                    .map(|t| t.id.clone())
+                    .collect(),
+            );
+        });
+    }));
+
+    // Everything in the chosen language not yet on disk, ignoring the search box
+    let language_ids: Vec<String> = {
+        let lang = selected_language.read();
+        let on_disk = on_disk.read();
+        if lang.is_empty() {
+            Vec::new()
+        } else {
+            translations
+                .iter()
+                .filter(|t| t.language == *lang && !on_disk.contains(&t.id))
+                .map(|t| t.id.clone())
+                .collect()
+        }
+    };
+    let language_pending = {
+        let state = queue_state.read();
+        language_ids
+            .iter()
+            .filter(|id| matches!(state.get(*id), Some(QueueItemState::Queued | QueueItemState::Downloading(_))))
+            .count()
+    };
     
     // Get unique languages for filter dropdown
     let languages = {
@@ -76,6 +127,28 @@ pub fn TranslationsModal(
                                 option { value: "{code}", "{name}" }
                             }
                         }
+                        if language_pending > 0 {
+                            button {
+                                class: "px-3 py-2 rounded bg-tertiary hover:bg-accent-secondary text-sm whitespace-nowrap",
+                                onclick: {
+                                    let queue = queue.clone();
+                                    let ids = language_ids.clone();
+                                    move |_| ids.iter().for_each(|id| queue.cancel(id))
+                                },
+                                "Cancel {language_pending} downloads"
+                            }
+                        } else if !language_ids.is_empty() {
+                            button {
+                                class: "px-3 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 text-sm font-medium whitespace-nowrap",
+                                title: "Download every translation in this language that isn't on disk yet",
+                                onclick: {
+                                    let queue = queue.clone();
+                                    let ids = language_ids.clone();
+                                    move |_| queue.enqueue(ids.clone())
+                                },
+                                "Download all ({language_ids.len()})"
+                            }
+                        }
                     }
                     div { class: "text-xs text-secondary",
                         "Found {grouped_translations.len()} translations"
@@ -85,10 +158,13 @@ pub fn TranslationsModal(
                     }
                 }
                 
+                ImportPanel { on_imported: move |t: Translation| on_translation_imported.call(t) }
+                IndexSourcesPanel { on_reloaded: move |list: Vec<Translation>| on_index_reloaded.call(list) }
+
                 // Translations list
                 div { class: "max-h-[60vh] overflow-y-auto space-y-2",
                     for t in &grouped_translations {
-                        TranslationRow { translation: t.clone() }
+                        TranslationRow { key: "{t.id}", translation: t.clone(), queue_state, on_changed: move |id: String| on_translation_changed.call(id) }
                     }
                     if grouped_translations.is_empty() {
                         div { class: "text-center py-8 text-secondary",
@@ -105,27 +181,90 @@ pub fn TranslationsModal(
     }
 }
 
+/// The download queue's state as a signal, kept current for as long as the component is mounted
+fn use_queue_snapshot() -> Signal<QueueSnapshot> {
+    let queue = use_context::<DownloadQueue>();
+    let mut queue_state = use_signal(QueueSnapshot::new);
+    use_future(move || {
+        let mut rx = queue.subscribe();
+        async move {
+            loop {
+                queue_state.set(rx.borrow_and_update().clone());
+                if rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    queue_state
+}
+
 #[component]
-fn TranslationRow(translation: Translation) -> Element {
-    let mut is_downloading = use_signal(|| false);
-    let mut downloaded = use_signal(|| false);
-    let mut download_error = use_signal(|| None::<String>);
+fn TranslationRow(translation: Translation, queue_state: Signal<QueueSnapshot>, on_changed: EventHandler<String>) -> Element {
+    let mut is_deleting = use_signal(|| false);
+    let mut status = use_signal(|| TranslationStatus::Available);
+    let mut disk_bytes = use_signal(|| 0u64);
+    let mut has_update = use_signal(|| false);
+    let mut delete_error = use_signal(|| None::<BibleError>);
     let name = translation.name.clone();
     let lang_label = translation.language_name.clone().unwrap_or(translation.language.clone());
     let abbr = translation.abbreviation.clone();
     let desc = translation.description.clone();
-    let id_for_status = translation.id.clone();
+    // Follows the props, so every action below acts on the translation the row currently shows
+    let id = use_memo(use_reactive!(|translation| translation.id.clone()));
+    let service = use_context::<CopyValue<SharedBibleService>>();
+    let queue = use_context::<DownloadQueue>();
+
+    let queued = use_memo(move || queue_state.read().get(&*id.read()).cloned());
+    let finished = use_memo(move || matches!(*queued.read(), Some(QueueItemState::Done)));
 
+    let refresh = use_callback(move |_: ()| {
+        spawn(async move {
+            let id = id.read().clone();
+            let svc = service.cloned().lock_owned().await;
+            status.set(svc.translation_status(&id));
+            has_update.set(svc.update_available(&id));
+            disk_bytes.set(svc.disk_usage(&id).await.unwrap_or(0));
+        });
+    });
+    // On open, when the row switches translation, and whenever a queued download of it completes
     use_effect(move || {
-        let id = id_for_status.clone();
+        let _ = id.read();
+        let _ = finished.read();
+        refresh(());
+    });
+
+    // Download, update and re-download all go through the queue, which works out which is needed
+    let enqueue = {
+        let queue = queue.clone();
+        use_callback(move |_: ()| {
+            delete_error.set(None);
+            queue.enqueue([id.read().clone()]);
+        })
+    };
+
+    let delete = move |_| {
+        let id = id.read().clone();
+        is_deleting.set(true);
+        delete_error.set(None);
         spawn(async move {
-            let svc = BibleService::new();
-            match svc.is_translation_downloaded(&id).await {
-                Ok(v) => downloaded.set(v),
-                Err(_) => downloaded.set(false),
+            let res = service.cloned().lock_owned().await.delete_translation(&id).await;
+            is_deleting.set(false);
+            match res {
+                Ok(()) => on_changed.call(id),
+                Err(e) => delete_error.set(Some(e)),
             }
+            refresh(());
         });
-    });
+    };
+
+    let current = status.read().clone();
+    let on_disk = matches!(current, TranslationStatus::Downloaded | TranslationStatus::Error);
+    let in_queue = queued.read().clone();
+    let error = match &in_queue {
+        Some(QueueItemState::Failed(e)) => Some(e.clone()),
+        _ => delete_error.read().clone(),
+    };
 
     rsx! {
         div { class: "p-4 rounded-lg border border-primary bg-secondary hover:bg-tertiary transition-colors",
@@ -139,46 +278,372 @@ fn TranslationRow(translation: Translation) -> Element {
                     if !desc.is_empty() && desc != name {
                         div { class: "text-xs text-secondary opacity-75 line-clamp-2", "{desc}" }
                     }
-                    if let Some(error) = download_error.read().as_ref() {
+                    if let Some(error) = error {
                         div { class: "text-xs text-red-600 dark:text-red-400 mt-2", "Error: {error}" }
                     }
                 }
                 
                 div { class: "flex flex-col items-end gap-2",
-                    if *downloaded.read() {
-                        div { class: "flex items-center gap-2",
-                            span { class: "text-xs px-3 py-1 rounded-full bg-green-100 text-green-700 dark:bg-green-900 dark:text-green-200 font-medium", "✓ Downloaded" }
+                    if let Some(QueueItemState::Queued | QueueItemState::Downloading(_)) = in_queue {
+                        {
+                            let (percent, label) = match in_queue {
+                                Some(QueueItemState::Downloading(p)) => progress_label(p),
+                                _ => (Some(0.0), "Waiting…".to_string()),
+                            };
+                            rsx! {
+                                div { class: "flex flex-col items-end gap-1 w-48",
+                                    div { class: "w-full h-2 rounded bg-tertiary overflow-hidden",
+                                        div {
+                                            class: if percent.is_some() { "h-full bg-blue-600 transition-all" } else { "h-full bg-blue-600 animate-pulse" },
+                                            style: "width: {percent.unwrap_or(100.0):.0}%",
+                                        }
+                                    }
+                                    span { class: "text-xs text-secondary tabular-nums", "{label}" }
+                                    button {
+                                        class: "px-3 py-1 rounded bg-tertiary hover:bg-accent-secondary text-xs",
+                                        onclick: move |_| queue.cancel(&id.read()),
+                                        "Cancel"
+                                    }
+                                }
+                            }
                         }
                     } else {
+                        match current {
+                            TranslationStatus::Downloaded => rsx! {
+                                span { class: "text-xs px-3 py-1 rounded-full bg-green-100 text-green-700 dark:bg-green-900 dark:text-green-200 font-medium", "✓ Downloaded" }
+                                if *has_update.read() {
+                                    button {
+                                        class: "px-3 py-1 rounded bg-blue-600 text-white hover:bg-blue-700 text-xs font-medium",
+                                        title: "The library has a newer edition of this translation",
+                                        onclick: move |_| enqueue(()),
+                                        "Update"
+                                    }
+                                }
+                            },
+                            TranslationStatus::Core => rsx! {
+                                span { class: "text-xs px-3 py-1 rounded-full bg-green-100 text-green-700 dark:bg-green-900 dark:text-green-200 font-medium", "Built in" }
+                            },
+                            TranslationStatus::Error => rsx! {
+                                span { class: "text-xs px-3 py-1 rounded-full bg-red-100 text-red-700 dark:bg-red-900 dark:text-red-200 font-medium", "⚠ Damaged" }
+                                button {
+                                    class: "px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 text-sm font-medium transition-colors",
+                                    onclick: move |_| enqueue(()),
+                                    "Re-download"
+                                }
+                            },
+                            TranslationStatus::Available => rsx! {
+                                button {
+                                    class: "px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 disabled:opacity-50 disabled:cursor-not-allowed text-sm font-medium transition-colors",
+                                    onclick: move |_| enqueue(()),
+                                    "Download"
+                                }
+                            },
+                        }
+                        if on_disk {
+                            div { class: "flex items-center gap-2",
+                                span { class: "text-xs text-secondary tabular-nums", "{format_size(*disk_bytes.read())}" }
+                                button {
+                                    class: "px-3 py-1 rounded bg-tertiary hover:bg-red-100 hover:text-red-700 dark:hover:bg-red-900 dark:hover:text-red-200 disabled:opacity-50 text-xs",
+                                    disabled: *is_deleting.read(),
+                                    onclick: delete,
+                                    if *is_deleting.read() { "Deleting…" } else { "Delete" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// "820 KB" or "4.5 MB"
+fn format_size(bytes: u64) -> String {
+    let kb = bytes as f64 / 1024.0;
+    if kb < 1024.0 {
+        format!("{:.0} KB", kb)
+    } else {
+        format!("{:.1} MB", kb / 1024.0)
+    }
+}
+
+/// Percent complete (when the size is known) and a "3.2 / 4.5 MB" label
+fn progress_label(progress: Option<DownloadProgress>) -> (Option<f64>, String) {
+    let Some(p) = progress else {
+        return (None, "Connecting…".to_string());
+    };
+    let mb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+    match p.total {
+        Some(total) => (p.fraction().map(|f| f * 100.0), format!("{:.1} / {:.1} MB", mb(p.received), mb(total))),
+        None => (None, format!("{:.1} MB", mb(p.received))),
+    }
+}
+
+/// Sideload a Bible file (OSIS, Beblia, Zefania, OpenSong XML or USFM/USX book files) from disk
+#[component]
+fn ImportPanel(on_imported: EventHandler<Translation>) -> Element {
+    let mut is_open = use_signal(|| false);
+    let mut name = use_signal(|| String::new());
+    let mut language = use_signal(|| String::new());
+    let mut is_importing = use_signal(|| false);
+    let mut import_error = use_signal(|| None::<BibleError>);
+    let mut imported_name = use_signal(|| None::<String>);
+    let service = use_context::<CopyValue<SharedBibleService>>();
+
+    rsx! {
+        div { class: "mb-4 rounded-lg border border-primary",
+            button {
+                class: "w-full flex items-center justify-between px-4 py-2 text-sm text-primary hover:bg-tertiary",
+                onclick: move |_| {
+                    let open = *is_open.read();
+                    is_open.set(!open);
+                },
+                span { "📂 Import from file" }
+                span { class: "text-xs text-secondary", if *is_open.read() { "Hide" } else { "Show" } }
+            }
+            if *is_open.read() {
+                div { class: "px-4 pb-4 space-y-3",
+                    div { class: "flex gap-3",
+                        input {
+                            class: "flex-1 px-3 py-2 border border-primary rounded bg-secondary text-primary placeholder-secondary text-sm",
+                            placeholder: "Name (optional, read from the file if left empty)",
+                            value: "{name.read()}",
+                            oninput: move |evt| name.set(evt.value())
+                        }
+                        input {
+                            class: "w-40 px-3 py-2 border border-primary rounded bg-secondary text-primary placeholder-secondary text-sm",
+                            placeholder: "Language code",
+                            value: "{language.read()}",
+                            oninput: move |evt| language.set(evt.value())
+                        }
+                    }
+                    input {
+                        r#type: "file",
+                        multiple: true,
+                        accept: ".xml,.osis,.usfm,.sfm,.usx",
+                        disabled: *is_importing.read(),
+                        class: "block w-full text-sm text-secondary",
+                        onchange: move |evt| {
+                            let files: Vec<PathBuf> = evt.files().iter().map(|f| f.path()).collect();
+                            if files.is_empty() {
+                                return;
+                            }
+                            let name_opt = Some(name.read().clone());
+                            let language_opt = Some(language.read().clone());
+                            is_importing.set(true);
+                            import_error.set(None);
+                            imported_name.set(None);
+                            spawn(async move {
+                                let mut svc = service.cloned().lock_owned().await;
+                                let res = svc.import_translation_files(&files, name_opt, language_opt).await;
+                                drop(svc);
+                                is_importing.set(false);
+                                match res {
+                                    Ok(t) => {
+                                        imported_name.set(Some(t.name.clone()));
+                                        name.set(String::new());
+                                        language.set(String::new());
+                                        on_imported.call(t);
+                                    }
+                                    Err(e) => import_error.set(Some(e)),
+                                }
+                            });
+                        }
+                    }
+                    div { class: "text-xs text-secondary",
+                        "Choose one XML Bible (OSIS, Beblia, Zefania, OpenSong) or all USFM/USX book files of a translation."
+                    }
+                    if *is_importing.read() {
+                        div { class: "flex items-center gap-2 text-xs text-secondary",
+                            div { class: "w-3 h-3 border border-blue-500 border-t-transparent rounded-full animate-spin" }
+                            "Importing…"
+                        }
+                    }
+                    if let Some(n) = imported_name.read().as_ref() {
+                        div { class: "text-xs text-green-700 dark:text-green-300", "✓ Imported {n}" }
+                    }
+                    if let Some(error) = import_error.read().as_ref() {
+                        div { class: "text-xs text-red-600 dark:text-red-400", "Error: {error}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where the translation list and downloads come from: index URLs and file mirrors, in fallback order
+#[component]
+fn IndexSourcesPanel(on_reloaded: EventHandler<Vec<Translation>>) -> Element {
+    let mut is_open = use_signal(|| false);
+    let mut index_urls = use_signal(String::new);
+    let mut mirrors = use_signal(String::new);
+    let mut is_saving = use_signal(|| false);
+    let mut save_error = use_signal(|| None::<BibleError>);
+    let service = use_context::<CopyValue<SharedBibleService>>();
+
+    let lines = |text: &str| -> Vec<String> {
+        text.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect()
+    };
+
+    rsx! {
+        div { class: "mb-4 rounded-lg border border-primary",
+            button {
+                class: "w-full flex items-center justify-between px-4 py-2 text-sm text-primary hover:bg-tertiary",
+                onclick: move |_| {
+                    let open = *is_open.read();
+                    if !open {
+                        let settings = Settings::load();
+                        index_urls.set(settings.index_urls.join("\n"));
+                        mirrors.set(settings.mirrors.join("\n"));
+                    }
+                    is_open.set(!open);
+                },
+                span { "🌐 Index and mirrors" }
+                span { class: "text-xs text-secondary", if *is_open.read() { "Hide" } else { "Show" } }
+            }
+            if *is_open.read() {
+                div { class: "px-4 pb-4 space-y-3",
+                    div { class: "text-xs text-secondary", "Translation indexes, one URL or file path per line, tried in order:" }
+                    textarea {
+                        class: "w-full h-20 px-3 py-2 border border-primary rounded bg-secondary text-primary text-xs font-mono",
+                        value: "{index_urls.read()}",
+                        oninput: move |evt| index_urls.set(evt.value())
+                    }
+                    div { class: "text-xs text-secondary", "Mirrors for translation files, one base URL per line (relative download links resolve against these):" }
+                    textarea {
+                        class: "w-full h-16 px-3 py-2 border border-primary rounded bg-secondary text-primary text-xs font-mono",
+                        value: "{mirrors.read()}",
+                        oninput: move |evt| mirrors.set(evt.value())
+                    }
+                    div { class: "flex items-center gap-3",
                         button {
-                            class: "px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 disabled:opacity-50 disabled:cursor-not-allowed text-sm font-medium transition-colors",
-                            disabled: *is_downloading.read(),
+                            class: "px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 disabled:opacity-50 text-sm font-medium",
+                            disabled: *is_saving.read(),
                             onclick: move |_| {
-                                let id = translation.id.clone();
-                                is_downloading.set(true);
-                                download_error.set(None);
+                                let settings = Settings { index_urls: lines(&index_urls.read()), mirrors: lines(&mirrors.read()), ..Settings::load() };
+                                is_saving.set(true);
+                                save_error.set(None);
                                 spawn(async move {
-                                    let svc = BibleService::new();
-                                    let res = svc.download_translation_xml(&id).await;
-                                    is_downloading.set(false);
+                                    let mut svc = service.cloned().lock_owned().await;
+                                    let res = match svc.apply_settings(&settings) {
+                                        Ok(()) => svc.load_translations().await,
+                                        Err(e) => Err(e),
+                                    };
+                                    drop(svc);
+                                    is_saving.set(false);
                                     match res {
-                                        Ok(_) => downloaded.set(true),
-                                        Err(e) => download_error.set(Some(e)),
+                                        Ok(list) => on_reloaded.call(list),
+                                        Err(e) => save_error.set(Some(e)),
                                     }
                                 });
                             },
-                            if *is_downloading.read() { 
-                                span { class: "flex items-center gap-2",
-                                    div { class: "w-3 h-3 border border-white border-t-transparent rounded-full animate-spin" }
-                                    "Downloading…"
+                            if *is_saving.read() { "Loading…" } else { "Save and reload" }
+                        }
+                        button {
+                            class: "px-3 py-2 rounded bg-tertiary hover:bg-accent-secondary text-sm",
+                            onclick: move |_| {
+                                let defaults = Settings::default();
+                                index_urls.set(defaults.index_urls.join("\n"));
+                                mirrors.set(defaults.mirrors.join("\n"));
+                            },
+                            "Defaults"
+                        }
+                    }
+                    if let Some(error) = save_error.read().as_ref() {
+                        div { class: "text-xs text-red-600 dark:text-red-400", "Error: {error}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// First start with nothing on disk: suggest a translation for the system language and fetch it
+/// once the user agrees. `choices` come ranked from `BibleService::first_run_choices`.
+#[component]
+pub fn FirstRunModal(choices: Vec<Translation>, on_confirm: EventHandler<Translation>, on_close: EventHandler<()>) -> Element {
+    let mut selected_id = use_signal(|| choices.first().map(|t| t.id.clone()).unwrap_or_default());
+    let mut confirmed = use_signal(|| None::<String>);
+    let queue_state = use_queue_snapshot();
+
+    let state = confirmed.read().as_ref().and_then(|id| queue_state.read().get(id).cloned());
+    let finished = use_memo(move || {
+        let id = confirmed.read().clone();
+        id.is_some_and(|id| matches!(queue_state.read().get(&id), Some(QueueItemState::Done)))
+    });
+    use_effect(move || {
+        if finished() {
+            on_close.call(());
+        }
+    });
+    let suggested = choices.first().map(|t| t.id.clone());
+
+    rsx! {
+        div { class: "fixed inset-0 z-50 flex items-center justify-center bg-black/50",
+            div { class: "bg-secondary rounded-xl shadow-xl w-full max-w-lg p-6 border border-primary space-y-4",
+                h2 { class: "text-xl font-semibold text-primary", "Welcome to StudyBible" }
+                p { class: "text-sm text-secondary",
+                    "Choose a translation to download for offline reading. You can add more later from the translations library."
+                }
+                select {
+                    class: "w-full px-3 py-2 border border-primary rounded bg-secondary text-primary text-sm",
+                    value: "{selected_id.read()}",
+                    disabled: confirmed.read().is_some(),
+                    onchange: move |evt| selected_id.set(evt.value()),
+                    for t in choices.iter() {
+                        option { value: "{t.id}",
+                            {
+                                let lang = t.language_name.clone().unwrap_or(t.language.clone());
+                                let hint = if Some(&t.id) == suggested.as_ref() { " (suggested)" } else { "" };
+                                format!("{} · {}{}", t.name, lang, hint)
+                            }
+                        }
+                    }
+                }
+                match state.clone() {
+                    Some(QueueItemState::Queued) | Some(QueueItemState::Downloading(_)) => {
+                        let (percent, label) = match &state {
+                            Some(QueueItemState::Downloading(p)) => progress_label(*p),
+                            _ => (Some(0.0), "Waiting…".to_string()),
+                        };
+                        rsx! {
+                            div { class: "space-y-1",
+                                div { class: "w-full h-2 rounded bg-tertiary overflow-hidden",
+                                    div {
+                                        class: if percent.is_some() { "h-full bg-blue-600 transition-all" } else { "h-full bg-blue-600 animate-pulse" },
+                                        style: "width: {percent.unwrap_or(100.0):.0}%",
+                                    }
                                 }
-                            } else { 
-                                "Download" 
+                                span { class: "text-xs text-secondary tabular-nums", "{label}" }
                             }
                         }
                     }
+                    Some(QueueItemState::Failed(e)) => rsx! {
+                        div { class: "text-xs text-red-600 dark:text-red-400", "Error: {e}" }
+                    },
+                    _ => rsx! {},
+                }
+                div { class: "flex justify-end gap-3",
+                    button {
+                        class: "px-4 py-2 rounded bg-tertiary hover:bg-accent-secondary text-sm",
+                        onclick: move |_| on_close.call(()),
+                        if confirmed.read().is_some() { "Continue in background" } else { "Not now" }
+                    }
+                    if !matches!(state, Some(QueueItemState::Queued | QueueItemState::Downloading(_))) {
+                        button {
+                            class: "px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 text-sm font-medium",
+                            onclick: move |_| {
+                                let id = selected_id.read().clone();
+                                if let Some(t) = choices.iter().find(|t| t.id == id) {
+                                    confirmed.set(Some(id));
+                                    on_confirm.call(t.clone());
+                                }
+                            },
+                            if matches!(state, Some(QueueItemState::Failed(_))) { "Retry" } else { "Download" }
+                        }
+                    }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -1 +1,84 @@
-// Removed legacy BibleDataManager; superseded by BibleService
\ No newline at end of file
+//! Book metadata compiled into the app
+
+// Removed legacy BibleDataManager; superseded by BibleService
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[derive(Deserialize)]
+struct NamedBook {
+    id: u32,
+    #[serde(default)]
+    tamil_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UnifiedIndex {
+    books: Vec<NamedBook>,
+}
+
+/// ISO 639-1 code, ISO 639-3 (and 639-2/B) codes and English name of languages indexes list
+const LANGUAGES: &[(&str, &[&str], &str)] = &[
+    ("ar", &["ara"], "arabic"),
+    ("bn", &["ben"], "bengali"),
+    ("de", &["deu", "ger"], "german"),
+    ("el", &["ell", "gre", "grc"], "greek"),
+    ("en", &["eng"], "english"),
+    ("es", &["spa"], "spanish"),
+    ("fr", &["fra", "fre"], "french"),
+    ("he", &["heb", "hbo"], "hebrew"),
+    ("hi", &["hin"], "hindi"),
+    ("id", &["ind"], "indonesian"),
+    ("it", &["ita"], "italian"),
+    ("ja", &["jpn"], "japanese"),
+    ("kn", &["kan"], "kannada"),
+    ("ko", &["kor"], "korean"),
+    ("la", &["lat"], "latin"),
+    ("ml", &["mal"], "malayalam"),
+    ("mr", &["mar"], "marathi"),
+    ("nl", &["nld", "dut"], "dutch"),
+    ("pt", &["por"], "portuguese"),
+    ("ru", &["rus"], "russian"),
+    ("sw", &["swa", "swh"], "swahili"),
+    ("ta", &["tam"], "tamil"),
+    ("te", &["tel"], "telugu"),
+    ("tl", &["tgl"], "tagalog"),
+    ("vi", &["vie"], "vietnamese"),
+    ("zh", &["zho", "chi", "cmn"], "chinese"),
+];
+
+/// A language code or name as indexes write it ("ta", "tam", "Tamil") in one form, the
+/// two-letter code where we know one. Unknown languages come back lowercased.
+pub fn language_code(language: &str) -> String {
+    let language = language.trim().to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(code, codes, name)| *code == language || codes.contains(&language.as_str()) || *name == language)
+        .map_or(language.clone(), |(code, _, _)| code.to_string())
+}
+
+/// Book names by book id for a translation language, if we carry names for it. Takes any form
+/// `language_code` knows ("ta", "tam", "tamil").
+pub fn localized_book_names(language: &str) -> Option<&'static HashMap<u32, String>> {
+    match language_code(language).as_str() {
+        "ta" => Some(tamil_book_names()),
+        _ => None,
+    }
+}
+
+/// `tamil_books.json`, with `unified_bible_index.json` filling any gaps
+fn tamil_book_names() -> &'static HashMap<u32, String> {
+    static NAMES: OnceLock<HashMap<u32, String>> = OnceLock::new();
+    NAMES.get_or_init(|| {
+        let unified: Vec<NamedBook> = serde_json::from_str::<UnifiedIndex>(include_str!("unified_bible_index.json"))
+            .map(|index| index.books)
+            .unwrap_or_default();
+        let tamil: Vec<NamedBook> = serde_json::from_str(include_str!("tamil_books.json")).unwrap_or_default();
+        unified
+            .into_iter()
+            .chain(tamil)
+            .filter_map(|b| b.tamil_name.map(|name| (b.id, name)))
+            .collect()
+    })
+}
@@ -0,0 +1,68 @@
+use std::fmt::Display;
+use std::path::Path;
+use thiserror::Error;
+
+/// Everything that can go wrong loading, importing or downloading Bible data.
+/// Cloneable so the UI can keep the last error in a signal and pick an action from the variant.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BibleError {
+    #[error("Translation '{0}' is not downloaded")]
+    NotDownloaded(String),
+    #[error("Translation '{0}' not found in index")]
+    UnknownTranslation(String),
+    #[error("Book {book_id} is not part of '{translation_id}'")]
+    UnknownBook { translation_id: String, book_id: u32 },
+    #[error("No verses found for {translation_id} book {book_id} chapter {chapter}")]
+    MissingChapter { translation_id: String, book_id: u32, chapter: u32 },
+    #[error("No translations available")]
+    NoTranslations,
+    #[error("Failed to parse {path}{}: {message}", at(.pos))]
+    ParseError { path: String, pos: Option<u64>, message: String },
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Failed to {action} {path}: {message}")]
+    Io { action: &'static str, path: String, message: String },
+    #[error("Failed to encode {path}: {message}")]
+    Serialize { path: String, message: String },
+    /// The file is there but fails verification (wrong size or checksum, or no readable text)
+    #[error("{path} is damaged: {reason}")]
+    Corrupt { path: String, reason: String },
+    #[error("Download cancelled")]
+    Cancelled,
+    /// A request the user made that can't be carried out as asked, e.g. an empty import selection
+    #[error("{0}")]
+    InvalidInput(String),
+}
+
+impl BibleError {
+    pub fn io(action: &'static str, path: &Path, err: impl Display) -> Self {
+        BibleError::Io { action, path: path.display().to_string(), message: err.to_string() }
+    }
+
+    /// XML or other positional parse failure at a byte offset
+    pub fn parse_at(path: &Path, pos: u64, err: impl Display) -> Self {
+        BibleError::ParseError { path: path.display().to_string(), pos: Some(pos), message: err.to_string() }
+    }
+
+    /// Failure turning data into JSON before it is written to `path`
+    pub fn serialize(path: &Path, err: serde_json::Error) -> Self {
+        BibleError::Serialize { path: path.display().to_string(), message: err.to_string() }
+    }
+
+    /// JSON parse failure; serde_json already reports line and column in its message
+    pub fn json(path: impl Display, err: serde_json::Error) -> Self {
+        BibleError::ParseError { path: path.to_string(), pos: None, message: err.to_string() }
+    }
+}
+
+impl From<reqwest::Error> for BibleError {
+    fn from(err: reqwest::Error) -> Self {
+        BibleError::Network(err.to_string())
+    }
+}
+
+fn at(pos: &Option<u64>) -> String {
+    pos.map(|p| format!(" at pos {}", p)).unwrap_or_default()
+}
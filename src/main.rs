@@ -1,14 +1,17 @@
 use dioxus::prelude::*;
 
 mod types;
+mod error;
 mod data;
 mod services;
 mod components;
 
 use types::*;
+use error::BibleError;
 use services::*;
-use components::layout::{Header, Sidebar};
-use components::modals::TranslationsModal;
+use std::collections::HashSet;
+use components::layout::{BookmarksPanel, Header, SearchResultsPanel, Sidebar};
+use components::modals::{FirstRunModal, TranslationsModal};
 
 fn main() {
     dioxus::launch(App);
@@ -16,11 +19,15 @@ fn main() {
 
 #[component]
 fn App() -> Element {
+    // One service for the whole session, so its caches and index survive between handlers
+    let service = use_context_provider(|| CopyValue::new(SharedBibleService::default()));
+    let queue = use_context_provider(|| DownloadQueue::new(service.cloned(), DEFAULT_CONCURRENT_DOWNLOADS));
+
     // Core app state
     let mut is_dark_theme = use_signal(|| false);
     let mut is_sidebar_open = use_signal(|| true);
     let mut is_loading = use_signal(|| true);
-    let mut load_error = use_signal(|| None::<String>);
+    let mut load_error = use_signal(|| None::<BibleError>);
     
     // Bible data state
     let mut translations = use_signal(|| Vec::<Translation>::new());
@@ -29,7 +36,14 @@ fn App() -> Element {
     let mut selected_book = use_signal(|| None::<Book>);
     let mut selected_translation = use_signal(|| None::<Translation>);
     let mut selected_chapter = use_signal(|| 1);
-    let bookmarks = use_signal(|| Vec::<Bookmark>::new());
+    let mut bookmarks = use_signal(|| {
+        read_bookmarks().unwrap_or_else(|e| {
+            eprintln!("[StudyBible] Could not read bookmarks: {}", e);
+            Vec::new()
+        })
+    });
+    // (book, chapter, verse) of each bookmark as numbered in the selected translation
+    let mut bookmarked = use_signal(HashSet::<(u32, u32, u32)>::new);
     let _highlights = use_signal(|| Vec::<TextHighlight>::new());
     
     // UI state
@@ -39,25 +53,41 @@ fn App() -> Element {
     let mut secondary_translation = use_signal(|| None::<Translation>);
     let mut secondary_verses = use_signal(|| Vec::<Verse>::new());
     let mut search_query = use_signal(|| String::new());
+    let mut search_results = use_signal(|| None::<SearchResult>);
+    let mut show_bookmarks = use_signal(|| false);
     let mut show_translations_modal = use_signal(|| false);
+    let mut first_run_choices = use_signal(|| None::<Vec<Translation>>);
+    let mut is_downloading_missing = use_signal(|| false);
     
     // Initialize data on startup
     use_effect(move || {
         spawn(async move {
-            let mut bible_service = BibleService::new();
-            // Ensure we have at least one translation
-            let _ = bible_service.ensure_default_translation().await;
-            
-            match bible_service.load_translations().await {
+            let mut bible_service = service.cloned().lock_owned().await;
+            let loaded = bible_service.load_translations().await;
+            if loaded.is_ok() {
+                // Catch truncated or damaged files before they show up as empty chapters; the
+                // checks can take seconds, so the service is free while they run
+                let checks = bible_service.pending_checks();
+                drop(bible_service);
+                let mut outcomes = Vec::new();
+                for check in checks {
+                    outcomes.push(check.run().await);
+                }
+                bible_service = service.cloned().lock_owned().await;
+                for (id, e) in bible_service.finish_checks(outcomes) {
+                    eprintln!("[StudyBible] {} needs downloading again: {}", id, e);
+                }
+            }
+            match loaded {
                 Ok(trans_list) => {
                     translations.set(trans_list.clone());
-                    // Choose first available translation
-                    let chosen = trans_list
-                        .iter()
-                        .find(|t| t.language.starts_with("en") || t.language_name.as_ref().map(|n| n.contains("English")).unwrap_or(false))
-                        .cloned()
-                        .or_else(|| trans_list.first().cloned());
-                    if let Some(chosen_trans) = chosen {
+                    // Nothing set up yet: let the user confirm which translation to fetch first
+                    match bible_service.first_run_choices().await {
+                        Ok(Some(choices)) => first_run_choices.set(Some(choices)),
+                        Ok(None) => {}
+                        Err(e) => eprintln!("[StudyBible] Could not prepare first-run choices: {}", e),
+                    }
+                    if let Some(chosen_trans) = bible_service.preferred_translation() {
                         let chosen_id = chosen_trans.id.clone();
                         selected_translation.set(Some(chosen_trans));
                         // Load books for chosen translation
@@ -74,7 +104,7 @@ fn App() -> Element {
                                             is_loading.set(false);
                                         }
                                         Err(e) => {
-                                            load_error.set(Some(format!("Failed to load verses: {}", e)));
+                                            load_error.set(Some(e));
                                             is_loading.set(false);
                                         }
                                     }
@@ -83,17 +113,17 @@ fn App() -> Element {
                                 }
                             }
                             Err(e) => {
-                                load_error.set(Some(format!("Failed to load books: {}", e)));
+                                load_error.set(Some(e));
                                 is_loading.set(false);
                             }
                         }
                     } else {
-                        load_error.set(Some("No translations available".to_string()));
+                        load_error.set(Some(BibleError::NoTranslations));
                         is_loading.set(false);
                     }
                 }
                 Err(e) => {
-                    load_error.set(Some(format!("Failed to load translations: {}", e)));
+                    load_error.set(Some(e));
                     is_loading.set(false);
                 }
             }
@@ -108,10 +138,10 @@ fn App() -> Element {
         let translation_id = selected_translation.read().as_ref().map(|t| t.id.clone());
         if let Some(trans_id) = translation_id {
             spawn(async move {
-                let mut bible_service = BibleService::new();
+                let mut bible_service = service.cloned().lock_owned().await;
                 match bible_service.load_verses(&trans_id, book.id, 1).await {
                     Ok(verses_list) => verses.set(verses_list),
-                    Err(e) => load_error.set(Some(format!("Failed to load verses: {}", e))),
+                    Err(e) => load_error.set(Some(e)),
                 }
             });
         }
@@ -120,9 +150,10 @@ fn App() -> Element {
             let sec_id = sec.id.clone();
             let bid = book.id;
             let ch = 1u32;
+            let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
             spawn(async move {
-                let mut svc = BibleService::new();
-                match svc.load_verses(&sec_id, bid, ch).await {
+                let mut svc = service.cloned().lock_owned().await;
+                match svc.load_parallel_verses(&primary_id, &sec_id, bid, ch).await {
                     Ok(vs) => secondary_verses.set(vs),
                     Err(_) => secondary_verses.set(Vec::new()),
                 }
@@ -137,7 +168,7 @@ fn App() -> Element {
             selected_translation.set(Some(translation.clone()));
             
             spawn(async move {
-                let mut bible_service = BibleService::new();
+                let mut bible_service = service.cloned().lock_owned().await;
                 match bible_service.load_books(&translation_id).await {
                     Ok(books_list) => {
                         books.set(books_list.clone());
@@ -149,10 +180,10 @@ fn App() -> Element {
                             let bid = first_book.id;
                             let ch = 1u32;
                             spawn(async move {
-                                let mut svc = BibleService::new();
+                                let mut svc = service.cloned().lock_owned().await;
                                 match svc.load_verses(&tid, bid, ch).await {
                                     Ok(vs) => verses.set(vs),
-                                    Err(e) => load_error.set(Some(format!("{}", e))),
+                                    Err(e) => load_error.set(Some(e)),
                                 }
                             });
                             // refresh secondary if selected
@@ -160,9 +191,10 @@ fn App() -> Element {
                                 let sec_id = sec.id.clone();
                                 let bid2 = bid;
                                 let ch2 = ch;
+                                let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
                                 spawn(async move {
-                                    let mut svc = BibleService::new();
-                                    match svc.load_verses(&sec_id, bid2, ch2).await {
+                                    let mut svc = service.cloned().lock_owned().await;
+                                    match svc.load_parallel_verses(&primary_id, &sec_id, bid2, ch2).await {
                                         Ok(vs) => secondary_verses.set(vs),
                                         Err(_) => secondary_verses.set(Vec::new()),
                                     }
@@ -172,12 +204,198 @@ fn App() -> Element {
                             }
                         }
                     }
-                    Err(e) => load_error.set(Some(format!("Failed to load books: {}", e))),
+                    Err(e) => load_error.set(Some(e)),
+                }
+            });
+        }
+    };
+
+    // Open a chapter of the current translation; false if the translation has no such chapter
+    let mut go_to_chapter = move |bid: u32, ch: u32| -> bool {
+        let tid = selected_translation.read().as_ref().map(|t| t.id.clone());
+        let book = books.read().iter().find(|b| b.id == bid && ch <= b.chapter_count).cloned();
+        let (Some(tid), Some(book)) = (tid, book) else {
+            return false;
+        };
+        selected_book.set(Some(book));
+        selected_chapter.set(ch);
+        search_results.set(None);
+
+        spawn(async move {
+            let mut svc = service.cloned().lock_owned().await;
+            match svc.load_verses(&tid, bid, ch).await {
+                Ok(list) => verses.set(list),
+                Err(err) => load_error.set(Some(err)),
+            }
+        });
+        // refresh secondary if selected
+        if let Some(sec) = &*secondary_translation.read() {
+            let sec_id = sec.id.clone();
+            let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
+            spawn(async move {
+                let mut svc = service.cloned().lock_owned().await;
+                match svc.load_parallel_verses(&primary_id, &sec_id, bid, ch).await {
+                    Ok(vs) => secondary_verses.set(vs),
+                    Err(_) => secondary_verses.set(Vec::new()),
+                }
+            });
+        }
+        true
+    };
+
+    let mut on_search_result_select = move |hit: VerseWithBook| {
+        go_to_chapter(hit.verse.book_id, hit.verse.chapter);
+    };
+
+    // Bookmarks made in a translation numbered differently open at the same verse here
+    let on_bookmark_open = move |bookmark: Bookmark| {
+        let Some(tid) = selected_translation.read().as_ref().map(|t| t.id.clone()) else {
+            return;
+        };
+        spawn(async move {
+            let (chapter, _) = service.cloned().lock_owned().await.bookmark_position(&bookmark, &tid);
+            if go_to_chapter(bookmark.book_id, chapter) {
+                show_bookmarks.set(false);
+            }
+        });
+    };
+
+    let on_bookmark_toggle = move |verse: Verse| {
+        spawn(async move {
+            let svc = service.cloned().lock_owned().await;
+            let mut list = bookmarks.read().clone();
+            match svc.toggle_bookmark(&mut list, &verse.translation_id, verse.book_id, verse.chapter, verse.verse) {
+                Ok(_) => bookmarks.set(list),
+                Err(e) => load_error.set(Some(e)),
+            }
+        });
+    };
+
+    use_effect(move || {
+        let list = bookmarks.read().clone();
+        let tid = selected_translation.read().as_ref().map(|t| t.id.clone());
+        spawn(async move {
+            let Some(tid) = tid else { return };
+            let svc = service.cloned().lock_owned().await;
+            let positions = list
+                .iter()
+                .map(|b| {
+                    let (chapter, verse) = svc.bookmark_position(b, &tid);
+                    (b.book_id, chapter, verse)
+                })
+                .collect();
+            bookmarked.set(positions);
+        });
+    });
+
+    // Reload the selected book and chapter, e.g. after the translation behind an error was downloaded
+    let reload_current = move || {
+        let tid = selected_translation.read().as_ref().map(|t| t.id.clone());
+        let bid = selected_book.read().as_ref().map(|b| b.id);
+        let ch = *selected_chapter.read();
+        if let Some(tid) = tid {
+            spawn(async move {
+                let mut svc = service.cloned().lock_owned().await;
+                match svc.load_books(&tid).await {
+                    Ok(books_list) => {
+                        let book = bid
+                            .and_then(|bid| books_list.iter().find(|b| b.id == bid))
+                            .or(books_list.first())
+                            .cloned();
+                        books.set(books_list);
+                        if let Some(book) = book {
+                            let ch = if book.id == bid.unwrap_or(0) { ch } else { 1 };
+                            selected_book.set(Some(book.clone()));
+                            selected_chapter.set(ch);
+                            match svc.load_verses(&tid, book.id, ch).await {
+                                Ok(vs) => {
+                                    verses.set(vs);
+                                    load_error.set(None);
+                                }
+                                Err(e) => load_error.set(Some(e)),
+                            }
+                        }
+                    }
+                    Err(e) => load_error.set(Some(e)),
                 }
             });
         }
     };
 
+    // `replace` throws away a damaged copy first so it is fetched again rather than kept
+    let mut on_download_missing = move |translation_id: String, replace: bool| {
+        is_downloading_missing.set(true);
+        spawn(async move {
+            // Hold the service only to plan and to take in the result, so reading and searching
+            // carry on while the bytes arrive and the text is imported
+            let prepared = async {
+                let mut svc = service.cloned().lock_owned().await;
+                let job = svc.prepare_download(&translation_id).await?;
+                // Only discard the old copy once we know where to fetch it from again
+                if replace {
+                    svc.remove_translation_files(&translation_id).await?;
+                }
+                Ok::<_, BibleError>(job)
+            }
+            .await;
+            let res = match prepared {
+                Ok(job) => match job.run(&CancelToken::new(), |_| {}).await {
+                    Ok(_) => {
+                        let imported = job.import().await;
+                        service.cloned().lock_owned().await.finish_download(&job, imported)
+                    }
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            is_downloading_missing.set(false);
+            match res {
+                Ok(()) => reload_current(),
+                Err(e) => load_error.set(Some(e)),
+            }
+        });
+    };
+
+    // Downloaded, updated or deleted: deleted sideloads leave the list, and the open chapter is
+    // reread if it belongs to that translation
+    let on_translation_changed = move |id: String| {
+        spawn(async move {
+            let mut svc = service.cloned().lock_owned().await;
+            if let Ok(list) = svc.load_translations().await {
+                translations.set(list);
+            }
+        });
+        if selected_translation.read().as_ref().is_some_and(|t| t.id == id) {
+            reload_current();
+        }
+    };
+
+    // Queued downloads keep going with the translations modal closed; pick up each one as it lands
+    use_future({
+        let queue = queue.clone();
+        move || {
+            let mut rx = queue.subscribe();
+            async move {
+                let mut done: HashSet<String> = HashSet::new();
+                while rx.changed().await.is_ok() {
+                    let finished: Vec<String> = {
+                        let state = rx.borrow_and_update();
+                        done.retain(|id| matches!(state.get(id), Some(QueueItemState::Done)));
+                        state
+                            .iter()
+                            .filter(|(id, s)| matches!(s, QueueItemState::Done) && !done.contains(*id))
+                            .map(|(id, _)| id.clone())
+                            .collect()
+                    };
+                    for id in finished {
+                        done.insert(id.clone());
+                        on_translation_changed(id);
+                    }
+                }
+            }
+        }
+    });
+
     rsx! {
         // Include CSS
         document::Link { rel: "stylesheet", href: asset!("assets/tailwind.css") }
@@ -202,7 +420,10 @@ fn App() -> Element {
                 selected_translation: selected_translation.read().clone(),
                 on_select_book: move |book: Book| on_book_select(book),
                 on_select_translation: move |id: String| on_translation_select(id),
-                on_open_bookmarks: move |_| {},
+                on_open_bookmarks: move |_| {
+                    let shown = *show_bookmarks.read();
+                    show_bookmarks.set(!shown);
+                },
                 on_open_settings: move |_| show_translations_modal.set(true),
                 on_toggle_sidebar: move |_| {
                     let current = *is_sidebar_open.read();
@@ -231,26 +452,24 @@ fn App() -> Element {
                     on_search: move |_| {
                         let trans_id_opt = selected_translation.read().as_ref().map(|t| t.id.clone());
                         let q = search_query.read().clone();
-                        let books_snapshot = books.read().clone();
                         if let Some(tid) = trans_id_opt {
+                            // "Jn 3:16" or an OSIS "John.3.16" opens the passage; a book name alone is
+                            // still searched as a word
+                            let passage = Some(&q)
+                                .filter(|q| q.chars().any(|c| c.is_ascii_digit()))
+                                .and_then(|q| parse_references(q).or_else(|_| parse_osis(q)).ok())
+                                .and_then(|ranges| ranges.into_iter().next());
+                            if let Some(range) = passage {
+                                if go_to_chapter(range.start.book_id, range.start.chapter) {
+                                    return;
+                                }
+                            }
                             if !q.trim().is_empty() {
                                 spawn(async move {
-                                    let mut bible_service = BibleService::new();
+                                    let mut bible_service = service.cloned().lock_owned().await;
                                     match bible_service.search_verses(&tid, &q).await {
-                                        Ok(results) => {
-                                            if let Some(v) = results.first() {
-                                                if let Some(book) = books_snapshot.iter().find(|b| b.id == v.book_id).cloned() {
-                                                    selected_book.set(Some(book.clone()));
-                                                    selected_chapter.set(v.chapter);
-                                                    let mut svc = BibleService::new();
-                                                    match svc.load_verses(&tid, v.book_id, v.chapter).await {
-                                                        Ok(list) => verses.set(list),
-                                                        Err(err) => load_error.set(Some(format!("{}", err))),
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        Err(e) => load_error.set(Some(format!("Search failed: {}", e))),
+                                        Ok(results) => search_results.set(Some(results)),
+                                        Err(e) => load_error.set(Some(e)),
                                     }
                                 });
                             }
@@ -278,9 +497,10 @@ fn App() -> Element {
                                     let bid = book.id;
                                     let ch = *selected_chapter.read();
                                     let sid = default_trans.id.clone();
+                                    let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
                                     spawn(async move {
-                                        let mut svc = BibleService::new();
-                                        match svc.load_verses(&sid, bid, ch).await {
+                                        let mut svc = service.cloned().lock_owned().await;
+                                        match svc.load_parallel_verses(&primary_id, &sid, bid, ch).await {
                                             Ok(vs) => secondary_verses.set(vs),
                                             Err(_) => secondary_verses.set(Vec::new()),
                                         }
@@ -300,9 +520,10 @@ fn App() -> Element {
                                 let ch = *selected_chapter.read();
                                 let bid = book.id;
                                 let tid_clone = tid.clone();
+                                let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
                                 spawn(async move {
-                                    let mut svc = BibleService::new();
-                                    match svc.load_verses(&tid_clone, bid, ch).await {
+                                    let mut svc = service.cloned().lock_owned().await;
+                                    match svc.load_parallel_verses(&primary_id, &tid_clone, bid, ch).await {
                                         Ok(vs) => secondary_verses.set(vs),
                                         Err(_) => secondary_verses.set(Vec::new()),
                                     }
@@ -333,10 +554,10 @@ fn App() -> Element {
                                     let mut verses_sig = verses.clone();
                                     let mut load_err = load_error.clone();
                                     spawn(async move {
-                                        let mut svc = BibleService::new();
+                                        let mut svc = service.cloned().lock_owned().await;
                                         match svc.load_verses(&tid, bid, new_ch).await {
                                             Ok(vs) => verses_sig.set(vs),
-                                            Err(e) => load_err.set(Some(format!("{}", e))),
+                                            Err(e) => load_err.set(Some(e)),
                                         }
                                     });
                                 }
@@ -345,9 +566,10 @@ fn App() -> Element {
                                     let sec_id = sec.id.clone();
                                     let bid2 = book.id;
                                     let ch2 = new_ch;
+                                    let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
                                     spawn(async move {
-                                        let mut svc = BibleService::new();
-                                        match svc.load_verses(&sec_id, bid2, ch2).await {
+                                        let mut svc = service.cloned().lock_owned().await;
+                                        match svc.load_parallel_verses(&primary_id, &sec_id, bid2, ch2).await {
                                             Ok(vs) => secondary_verses.set(vs),
                                             Err(_) => secondary_verses.set(Vec::new()),
                                         }
@@ -370,10 +592,10 @@ fn App() -> Element {
                                     let mut verses_sig = verses.clone();
                                     let mut load_err = load_error.clone();
                                     spawn(async move {
-                                        let mut svc = BibleService::new();
+                                        let mut svc = service.cloned().lock_owned().await;
                                         match svc.load_verses(&tid, bid, new_ch).await {
                                             Ok(vs) => verses_sig.set(vs),
-                                            Err(e) => load_err.set(Some(format!("{}", e))),
+                                            Err(e) => load_err.set(Some(e)),
                                         }
                                     });
                                 }
@@ -382,9 +604,10 @@ fn App() -> Element {
                                     let sec_id = sec.id.clone();
                                     let bid2 = book.id;
                                     let ch2 = new_ch;
+                                    let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
                                     spawn(async move {
-                                        let mut svc = BibleService::new();
-                                        match svc.load_verses(&sec_id, bid2, ch2).await {
+                                        let mut svc = service.cloned().lock_owned().await;
+                                        match svc.load_parallel_verses(&primary_id, &sec_id, bid2, ch2).await {
                                             Ok(vs) => secondary_verses.set(vs),
                                             Err(_) => secondary_verses.set(Vec::new()),
                                         }
@@ -415,10 +638,10 @@ fn App() -> Element {
                                     let tid = trans.id.clone();
                                     let bid = book.id;
                                     spawn(async move {
-                                        let mut svc = BibleService::new();
+                                        let mut svc = service.cloned().lock_owned().await;
                                         match svc.load_verses(&tid, bid, ch).await {
                                             Ok(vs) => verses.set(vs),
-                                            Err(e) => load_error.set(Some(format!("{}", e))),
+                                            Err(e) => load_error.set(Some(e)),
                                         }
                                     });
                                 }
@@ -427,9 +650,10 @@ fn App() -> Element {
                                     let sec_id = sec.id.clone();
                                     let bid2 = book.id;
                                     let ch2 = ch;
+                                    let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
                                     spawn(async move {
-                                        let mut svc = BibleService::new();
-                                        match svc.load_verses(&sec_id, bid2, ch2).await {
+                                        let mut svc = service.cloned().lock_owned().await;
+                                        match svc.load_parallel_verses(&primary_id, &sec_id, bid2, ch2).await {
                                             Ok(vs) => secondary_verses.set(vs),
                                             Err(_) => secondary_verses.set(Vec::new()),
                                         }
@@ -461,6 +685,45 @@ fn App() -> Element {
                             class: "text-center max-w-md mx-auto bg-red-50 dark:bg-red-900 border border-red-200 dark:border-red-700 rounded-lg p-6",
                             h3 { class: "text-lg font-semibold text-red-800 dark:text-red-200 mb-2", "Error Loading Bible Data" }
                             p { class: "text-sm text-red-600 dark:text-red-300", "{error}" }
+                            match error {
+                                BibleError::NotDownloaded(id) => {
+                                    let id = id.clone();
+                                    rsx! {
+                                        button {
+                                            class: "mt-4 px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 disabled:opacity-50 text-sm font-medium",
+                                            disabled: *is_downloading_missing.read(),
+                                            onclick: move |_| on_download_missing(id.clone(), false),
+                                            if *is_downloading_missing.read() { "Downloading…" } else { "Download translation" }
+                                        }
+                                    }
+                                }
+                                BibleError::Corrupt { .. } => {
+                                    let id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
+                                    rsx! {
+                                        button {
+                                            class: "mt-4 px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 disabled:opacity-50 text-sm font-medium",
+                                            disabled: *is_downloading_missing.read(),
+                                            onclick: move |_| on_download_missing(id.clone(), true),
+                                            if *is_downloading_missing.read() { "Downloading…" } else { "Re-download" }
+                                        }
+                                    }
+                                }
+                                BibleError::Network(_) => rsx! {
+                                    button {
+                                        class: "mt-4 px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-700 text-sm font-medium",
+                                        onclick: move |_| reload_current(),
+                                        "Retry"
+                                    }
+                                },
+                                BibleError::ParseError { .. } | BibleError::UnsupportedFormat(_) | BibleError::UnknownTranslation(_) => rsx! {
+                                    button {
+                                        class: "mt-4 px-4 py-2 rounded bg-tertiary hover:bg-accent-secondary text-sm font-medium",
+                                        onclick: move |_| show_translations_modal.set(true),
+                                        "Manage translations"
+                                    }
+                                },
+                                _ => rsx! {},
+                            }
                         }
                     }
                 }
@@ -471,6 +734,27 @@ fn App() -> Element {
                         div {
                             class: format!("{} mx-auto p-8", if *is_parallel_view.read() && *is_parallel_by_columns.read() { "max-w-6xl" } else { "max-w-4xl" }),
                             
+                            if *show_bookmarks.read() {
+                                BookmarksPanel {
+                                    bookmarks: bookmarks.read().clone(),
+                                    translations: translations.read().clone(),
+                                    on_open: on_bookmark_open,
+                                    on_close: move |_| show_bookmarks.set(false),
+                                }
+                            }
+
+                            if let Some(results) = &*search_results.read() {
+                                SearchResultsPanel {
+                                    results: results.clone(),
+                                    reference_style: selected_translation
+                                        .read()
+                                        .as_ref()
+                                        .map_or(ReferenceStyle::Long, |t| ReferenceStyle::for_language(&t.language)),
+                                    on_select_result: move |hit: VerseWithBook| on_search_result_select(hit),
+                                    on_close: move |_| search_results.set(None),
+                                }
+                            }
+
                             if let Some(book) = &*selected_book.read() {
                                 div {
                                     // Chapter header
@@ -478,7 +762,7 @@ fn App() -> Element {
                                         class: "mb-8 pb-6 border-b border-primary",
                                         h1 {
                                             class: "text-3xl font-bold text-primary mb-2",
-                                            "{book.name} {selected_chapter.read()}"
+                                            "{book.display_name()} {selected_chapter.read()}"
                                         }
                                         if let Some(translation) = &*selected_translation.read() {
                                             p {
@@ -510,9 +794,10 @@ fn App() -> Element {
                                                                 let ch = *selected_chapter.read();
                                                                 let bid = book.id;
                                                                 let tid_clone = tid.clone();
+                                                                let primary_id = selected_translation.read().as_ref().map(|t| t.id.clone()).unwrap_or_default();
                                                                 spawn(async move {
-                                                                    let mut svc = BibleService::new();
-                                                                    match svc.load_verses(&tid_clone, bid, ch).await {
+                                                                    let mut svc = service.cloned().lock_owned().await;
+                                                                    match svc.load_parallel_verses(&primary_id, &tid_clone, bid, ch).await {
                                                                         Ok(vs) => secondary_verses.set(vs),
                                                                         Err(_) => secondary_verses.set(Vec::new()),
                                                                     }
@@ -599,9 +884,22 @@ fn App() -> Element {
                                                             "{verse.verse}"
                                                         }
                                                         p {
-                                                            class: "text-primary leading-relaxed",
+                                                            class: "text-primary leading-relaxed flex-1",
                                                             "{verse.text}"
                                                         }
+                                                        button {
+                                                            class: if bookmarked.read().contains(&(verse.book_id, verse.chapter, verse.verse)) {
+                                                                "flex-shrink-0 text-sm"
+                                                            } else {
+                                                                "flex-shrink-0 text-sm opacity-0 group-hover:opacity-50"
+                                                            },
+                                                            title: "Bookmark",
+                                                            onclick: {
+                                                                let verse = verse.clone();
+                                                                move |_| on_bookmark_toggle(verse.clone())
+                                                            },
+                                                            "🔖"
+                                                        }
                                                     }
                                                 }
                                             }
@@ -627,7 +925,31 @@ fn App() -> Element {
                 }
             }
             // Modals
-            TranslationsModal { is_open: *show_translations_modal.read(), translations: translations.read().clone(), on_close: move |_| show_translations_modal.set(false) }
+            TranslationsModal {
+                is_open: *show_translations_modal.read(),
+                translations: translations.read().clone(),
+                on_close: move |_| show_translations_modal.set(false),
+                on_translation_imported: move |t: Translation| translations.write().push(t),
+                on_translation_changed: move |id: String| on_translation_changed(id),
+                on_index_reloaded: move |list: Vec<Translation>| translations.set(list),
+            }
+
+            if let Some(choices) = first_run_choices.read().clone() {
+                FirstRunModal {
+                    choices,
+                    on_confirm: move |t: Translation| {
+                        let id = t.id.clone();
+                        selected_translation.set(Some(t));
+                        queue.enqueue([id.clone()]);
+                        spawn(async move {
+                            if let Err(e) = service.cloned().lock_owned().await.set_default_translation(&id) {
+                                eprintln!("[StudyBible] Could not save default translation: {}", e);
+                            }
+                        });
+                    },
+                    on_close: move |_| first_run_choices.set(None),
+                }
+            }
         }
     }
 }
\ No newline at end of file
@@ -1,272 +1,542 @@
+use crate::data;
+use crate::error::BibleError;
 use crate::types::*;
 use std::collections::HashMap;
 use directories::ProjectDirs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tokio::fs;
-use thiserror::Error;
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+mod catalogue;
+mod download;
+mod first_run;
+mod formats;
+mod index_cache;
+mod paratext;
+mod queue;
+pub mod reference;
+mod search;
+pub mod settings;
+pub mod sources;
+mod store;
+#[cfg(test)]
+mod testing;
+mod verify;
+mod versification;
+
+pub use download::{CancelToken, DownloadJob, DownloadProgress};
+pub use queue::{DownloadQueue, QueueItemState, QueueSnapshot, DEFAULT_CONCURRENT_DOWNLOADS};
+pub use reference::{format_references, parse_osis, parse_references, ReferenceStyle};
+pub use verify::DownloadCheck;
+use download::RemoteFile;
+use search::SearchIndex;
+use sources::{BibleSource, LocalFileSource, RemoteIndexSource, StoreSource};
+use settings::Settings;
+use store::ChapterStore;
+
+/// The session's one `BibleService`, shared with components through Dioxus context.
+/// Lock it for one operation at a time so caches stay consistent.
+pub type SharedBibleService = Arc<tokio::sync::Mutex<BibleService>>;
 
 /// Service for managing Bible data operations
 pub struct BibleService {
     translations: Vec<Translation>,
     books_cache: HashMap<String, Vec<Book>>,
     verses_cache: HashMap<String, Vec<Verse>>,
-    hb_index_map: HashMap<String, HbEntryMinimal>,
+    /// Asked in order; the first source with an answer wins
+    sources: Vec<Box<dyn BibleSource>>,
+    /// Translations whose files failed verification, with the reason
+    damaged: HashMap<String, BibleError>,
 }
 
 impl BibleService {
+    /// Service over the default sources: translations index, chapter stores, then local files
     pub fn new() -> Self {
+        Self::with_settings(&Settings::load())
+    }
+
+    /// Default sources, with the index URLs and mirrors from `settings`
+    pub fn with_settings(settings: &Settings) -> Self {
+        Self::with_sources(Self::default_sources(settings))
+    }
+
+    fn default_sources(settings: &Settings) -> Vec<Box<dyn BibleSource>> {
+        vec![
+            Box::new(RemoteIndexSource::from_settings(settings)),
+            Box::new(StoreSource::new()),
+            Box::new(LocalFileSource::new()),
+        ]
+    }
+
+    /// Save new index settings and switch to them; the next `load_translations` reads the new index
+    pub fn apply_settings(&mut self, settings: &Settings) -> Result<(), BibleError> {
+        settings.save()?;
+        self.sources = Self::default_sources(settings);
+        self.books_cache.clear();
+        self.verses_cache.clear();
+        Ok(())
+    }
+
+    /// Service over the given sources, highest priority first
+    pub fn with_sources(sources: Vec<Box<dyn BibleSource>>) -> Self {
         Self {
             translations: Vec::new(),
             books_cache: HashMap::new(),
             verses_cache: HashMap::new(),
-            hb_index_map: HashMap::new(),
+            sources,
+            damaged: HashMap::new(),
         }
     }
 
-
-    /// Load available translations: prefer local HB_index, fallback to remote, then bundled
-    pub async fn load_translations(&mut self) -> Result<Vec<Translation>, String> {
-        // Try local HB_index submodule first
-        match self.fetch_local_hb_index().await {
-            Ok(list) => {
-                self.translations = list.clone();
-                return Ok(list);
-            }
-            Err(err) => {
-                eprintln!("[BibleService] Local HB_index failed: {}. Trying remote.", err);
+    /// Load available translations from every source. When several sources list the same id,
+    /// the entry from the higher-priority source is kept.
+    pub async fn load_translations(&mut self) -> Result<Vec<Translation>, BibleError> {
+        let mut list: Vec<Translation> = Vec::new();
+        let mut last_error = None;
+        for source in self.sources.iter_mut() {
+            match source.list_translations().await {
+                Ok(found) => {
+                    for t in found {
+                        if !list.iter().any(|known| known.id == t.id) {
+                            list.push(t);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[BibleService] Listing translations from {} failed: {}", source.name(), e);
+                    last_error = Some(e);
+                }
             }
         }
+        if let (true, Some(e)) = (list.is_empty(), last_error) {
+            return Err(e);
+        }
+        self.translations = list.clone();
+        Ok(list)
+    }
 
-        // Try remote HB_index as fallback
-        match self.fetch_and_cache_remote_index().await {
-            Ok(list) => {
-                self.translations = list.clone();
-                Ok(list)
-            }
-            Err(err) => {
-                eprintln!("[BibleService] Remote index failed: {}. Falling back to bundled.", err);
-                let translations_json = include_str!("data/translations_index.json");
-                match serde_json::from_str::<TranslationIndex>(translations_json) {
-                    Ok(data) => {
-                        self.translations = data.translations.clone();
-                        Ok(data.translations)
-                    },
-                    Err(e) => Err(format!("Failed to load translations: {}", e)),
+    /// Everything needed to fetch a translation, so the download itself can run without the service
+    pub async fn prepare_download(&self, translation_id: &str) -> Result<DownloadJob, BibleError> {
+        let remote = self
+            .remote_file(translation_id)
+            .ok_or_else(|| BibleError::UnknownTranslation(translation_id.to_string()))?;
+        let dir = app_data_dir()?.join("translations");
+        ensure_dir(&dir).await?;
+        Ok(DownloadJob {
+            translation_id: translation_id.to_string(),
+            remote,
+            dest: dir.join(format!("{}.xml", translation_id)),
+            retry_delay: download::RETRY_BASE_DELAY,
+        })
+    }
+
+    /// Take in a download that `DownloadJob::import` has verified and imported, with the outcome
+    /// of that import. Quick, so the service is only locked briefly.
+    pub fn finish_download(&mut self, job: &DownloadJob, imported: Result<(), BibleError>) -> Result<(), BibleError> {
+        let translation_id = job.translation_id.as_str();
+        // Anything cached before the download was the standard fallback, not this text
+        self.forget_cached(translation_id);
+        self.record_verification(translation_id, imported)?;
+        // Remember which index entry this file came from, to spot updates later
+        write_downloaded_from(translation_id, &job.remote)
+    }
+
+    /// Whether a translation's files are on disk with a chapter store built from them
+    pub fn has_current_store(&self, translation_id: &str) -> bool {
+        let Ok(Some((_, _, stamp))) = current_source(translation_id) else {
+            return false;
+        };
+        store_path_for_translation(translation_id)
+            .and_then(|path| ChapterStore::open(&path))
+            .is_ok_and(|store| store.is_current(&stamp))
+    }
+
+    fn remote_file(&self, translation_id: &str) -> Option<RemoteFile> {
+        self.sources.iter().find_map(|s| s.remote_file(translation_id))
+    }
+
+    /// Whether the index now lists a different file (size, checksum, year or status) than the one
+    /// downloaded. Downloading again replaces the file in place, so bookmarks and notes tied to
+    /// the translation id stay as they are.
+    pub fn update_available(&self, translation_id: &str) -> bool {
+        let Some(remote) = self.remote_file(translation_id) else {
+            return false;
+        };
+        match read_downloaded_from(translation_id) {
+            Some(downloaded) => downloaded.is_superseded_by(&remote),
+            // Downloaded before entries were recorded: the size on disk is all there is to go by
+            None => match (remote.size, source_for_translation(translation_id)) {
+                (Some(size), Ok(Some((path, SourceKind::Xml)))) => {
+                    std::fs::metadata(&path).is_ok_and(|m| m.len() != size)
                 }
-            }
+                _ => false,
+            },
         }
     }
 
-    async fn fetch_and_cache_remote_index(&mut self) -> Result<Vec<Translation>, FetchError> {
-        let client = Client::new();
-        let url = "https://raw.githubusercontent.com/SujithChristopher/HB_index/master/bible-translations-index.json";
-        let resp = client.get(url).send().await?.error_for_status()?;
-        let bytes = resp.bytes().await?;
-        
-        // Try new HB_index format first
-        if let Ok(hb_index) = serde_json::from_slice::<HbIndex>(&bytes) {
-            let mut map = HashMap::new();
-            let mut translations = Vec::new();
-            
-            for lang in hb_index.languages {
-                for trans in lang.translations {
-                    // Create Translation from HbTranslation
-                    let translation = Translation {
-                        id: trans.id.clone(),
-                        name: trans.name.clone(),
-                        abbreviation: extract_abbreviation(&trans.name),
-                        language: lang.iso_code.clone().unwrap_or_else(|| lang.language.clone().to_lowercase()),
-                        language_name: Some(lang.native_name.clone().unwrap_or(lang.language.clone())),
-                        description: trans.metadata.as_ref()
-                            .and_then(|m| m.info.clone())
-                            .unwrap_or_else(|| trans.name.clone()),
-                        bundled: false,
-                        priority: 0,
-                    };
-                    translations.push(translation);
-                    
-                    // Store download URL mapping
-                    map.insert(
-                        trans.id,
-                        HbEntryMinimal { download_url: trans.download_url },
-                    );
-                }
+    /// Bytes a translation takes up on disk: its source, chapter store, search index and any partial download
+    pub async fn disk_usage(&self, translation_id: &str) -> Result<u64, BibleError> {
+        let mut total = 0;
+        for path in translation_paths(translation_id)? {
+            total += size_on_disk(&path).await;
+        }
+        Ok(total)
+    }
+
+    /// Remove a translation from disk. Sideloaded translations also leave the local registry;
+    /// bookmarks are kept, so downloading it again brings them back.
+    pub async fn delete_translation(&mut self, translation_id: &str) -> Result<(), BibleError> {
+        self.remove_translation_files(translation_id).await?;
+        let mut registry = read_local_registry()?;
+        let before = registry.len();
+        registry.retain(|t| t.id != translation_id);
+        if registry.len() != before {
+            write_local_registry(&registry)?;
+            self.translations.retain(|t| t.id != translation_id);
+        }
+        Ok(())
+    }
+
+    /// Remember how a check of the translation's current files went, and pass the outcome on
+    fn record_verification(&mut self, translation_id: &str, result: Result<(), BibleError>) -> Result<(), BibleError> {
+        let Some((_, _, stamp)) = current_source(translation_id)? else {
+            return result;
+        };
+        let verified_path = verified_path()?;
+        let mut verified = verify::read_verified(&verified_path);
+        match &result {
+            Ok(()) => {
+                self.damaged.remove(translation_id);
+                verified.insert(translation_id.to_string(), stamp);
+            }
+            Err(e) => {
+                eprintln!("[BibleService] {} failed verification: {}", translation_id, e);
+                self.damaged.insert(translation_id.to_string(), e.clone());
+                verified.remove(translation_id);
             }
-            
-            self.hb_index_map = map;
-            return Ok(translations);
         }
-        
-        // Fallback to legacy format
-        let entries = parse_hb_entries(&bytes)?;
-        let mut map = HashMap::new();
-        let translations = entries
+        verify::write_verified(&verified_path, &verified)?;
+        result
+    }
+
+    /// Checks owed by translations with files in `translations/` that changed since they last
+    /// passed. Quick; run them with `DownloadCheck::run` while the service is unlocked, then
+    /// hand the outcomes to `finish_checks`.
+    pub fn pending_checks(&self) -> Vec<DownloadCheck> {
+        let verified = verified_path().map(|p| verify::read_verified(&p)).unwrap_or_default();
+        self.translations
             .iter()
-            .map(|e| Translation {
-                id: e.id.clone(),
-                name: e.name.clone(),
-                abbreviation: e.abbr.clone().unwrap_or_else(|| extract_abbreviation(&e.name)),
-                language: e.lang.clone().unwrap_or_else(|| "unknown".into()),
-                language_name: e.lang_name.clone(),
-                description: e.description.clone().unwrap_or_else(|| e.name.clone()),
-                bundled: false,
-                priority: 0,
+            .filter_map(|t| {
+                let (path, _, stamp) = current_source(&t.id).ok()??;
+                (verified.get(&t.id) != Some(&stamp)).then(|| DownloadCheck::new(t.id.clone(), path, self.remote_file(&t.id)))
             })
-            .collect::<Vec<_>>();
-        for e in entries.into_iter() {
-            map.insert(
-                e.id,
-                HbEntryMinimal { download_url: e.download_url },
-            );
+            .collect()
+    }
+
+    /// Remember the outcomes of `pending_checks`; returns the translations that failed
+    pub fn finish_checks(&mut self, outcomes: Vec<(String, Result<(), BibleError>)>) -> Vec<(String, BibleError)> {
+        outcomes
+            .into_iter()
+            .filter_map(|(id, result)| self.record_verification(&id, result).err().map(|e| (id, e)))
+            .collect()
+    }
+
+    /// Where a translation stands: on disk and healthy, on disk but damaged, bundled, or only listed
+    pub fn translation_status(&self, translation_id: &str) -> TranslationStatus {
+        if self.damaged.contains_key(translation_id) {
+            TranslationStatus::Error
+        } else if matches!(source_for_translation(translation_id), Ok(Some(_))) {
+            TranslationStatus::Downloaded
+        } else if self.translations.iter().any(|t| t.id == translation_id && t.bundled) {
+            TranslationStatus::Core
+        } else {
+            TranslationStatus::Available
         }
-        self.hb_index_map = map;
-        Ok(translations)
     }
 
-    async fn fetch_local_hb_index(&mut self) -> Result<Vec<Translation>, String> {
-        // Try to read local HB_index submodule
-        let local_path = std::path::Path::new("HB_index/bible-translations-index.json");
-        if !local_path.exists() {
-            return Err("Local HB_index file not found".to_string());
+    /// Delete a translation's source, chapter store, search index and any partial download
+    pub async fn remove_translation_files(&mut self, translation_id: &str) -> Result<(), BibleError> {
+        for path in translation_paths(translation_id)? {
+            let removed = if path.is_dir() { fs::remove_dir_all(&path).await } else { fs::remove_file(&path).await };
+            match removed {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(BibleError::io("remove", &path, e)),
+            }
         }
 
-        let content = tokio::fs::read_to_string(local_path).await
-            .map_err(|e| format!("Failed to read local HB_index: {}", e))?;
+        let verified_path = verified_path()?;
+        let mut verified = verify::read_verified(&verified_path);
+        if verified.remove(translation_id).is_some() {
+            verify::write_verified(&verified_path, &verified)?;
+        }
+        self.damaged.remove(translation_id);
+        self.forget_cached(translation_id);
+        Ok(())
+    }
 
-        // Parse the local HB_index format
-        let hb_index: HbIndex = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse local HB_index: {}", e))?;
+    /// Drop cached books and chapters of a translation whose source changed
+    fn forget_cached(&mut self, translation_id: &str) {
+        self.books_cache.remove(translation_id);
+        self.verses_cache.retain(|key, _| !key.starts_with(&format!("{}_", translation_id)));
+    }
 
-        let mut map = HashMap::new();
-        let mut translations = Vec::new();
-        
-        for lang in hb_index.languages {
-            for trans in lang.translations {
-                // Create Translation from HbTranslation
-                let translation = Translation {
-                    id: trans.id.clone(),
-                    name: trans.name.clone(),
-                    abbreviation: extract_abbreviation(&trans.name),
-                    language: lang.iso_code.clone().unwrap_or_else(|| lang.language.clone().to_lowercase()),
-                    language_name: Some(lang.native_name.clone().unwrap_or(lang.language.clone())),
-                    description: trans.metadata.as_ref()
-                        .and_then(|m| m.info.clone())
-                        .unwrap_or_else(|| trans.name.clone()),
-                    bundled: false,
-                    priority: 0,
-                };
-                translations.push(translation);
-                
-                // Store download URL mapping
-                map.insert(
-                    trans.id,
-                    HbEntryMinimal { download_url: trans.download_url },
-                );
+    /// Sideload a Bible from disk: one OSIS/Beblia/Zefania/OpenSong XML file, or a set of USFM/USX
+    /// book files. The text is validated, copied into `translations/` under a fresh id and registered
+    /// so `load_translations` lists it.
+    pub async fn import_translation_files(
+        &mut self,
+        files: &[PathBuf],
+        name: Option<String>,
+        language: Option<String>,
+    ) -> Result<Translation, BibleError> {
+        let first = files.first().ok_or_else(|| BibleError::InvalidInput("No files selected for import".to_string()))?;
+        let kind = SourceKind::for_import(first);
+        if kind == SourceKind::Xml && files.len() > 1 {
+            return Err(BibleError::InvalidInput("Import one XML Bible at a time".to_string()));
+        }
+
+        let info = match kind {
+            SourceKind::Xml => {
+                let format = formats::detect(first)?;
+                format.info(first).unwrap_or_default()
+            }
+            _ => formats::FileInfo::default(),
+        };
+        let title = name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .or(info.title)
+            .or_else(|| first.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+            .unwrap_or_else(|| "Imported Bible".to_string());
+        let language = language
+            .map(|l| l.trim().to_lowercase())
+            .filter(|l| !l.is_empty())
+            .or(info.language.map(|l| l.to_lowercase()));
+        let id = self.unique_local_id(&title)?;
+
+        match kind {
+            SourceKind::Xml => {
+                let dir = app_data_dir()?.join("translations");
+                ensure_dir(&dir).await?;
+                let dest = dir.join(format!("{}.xml", id));
+                fs::copy(first, &dest).await.map_err(|e| BibleError::io("copy", first, e))?;
+                // Importing the store doubles as validation: it fails on unreadable or empty files
+                if let Err(e) = import_translation(&id) {
+                    let _ = fs::remove_file(&dest).await;
+                    return Err(e);
+                }
+            }
+            _ => {
+                self.import_paratext_files(&id, files, kind).await?;
             }
         }
-        
-        self.hb_index_map = map;
-        Ok(translations)
-    }
-
-    pub async fn download_translation_xml(&self, translation_id: &str) -> Result<PathBuf, String> {
-        let entry = self
-            .hb_index_map
-            .get(translation_id)
-            .ok_or_else(|| format!("Translation '{}' not found in index", translation_id))?;
-        let url = entry
-            .download_url
-            .as_ref()
-            .ok_or_else(|| format!("No download URL for '{}'", translation_id))?;
+
+        let translation = Translation {
+            id,
+            abbreviation: extract_abbreviation(&title),
+            language: language.clone().unwrap_or_else(|| "unknown".into()),
+            language_name: language,
+            description: format!(
+                "Imported from {}",
+                first.file_name().and_then(|s| s.to_str()).unwrap_or("a local file")
+            ),
+            name: title,
+            bundled: false,
+            priority: 0,
+            versification: Versification::default(),
+            coverage: None,
+        };
+        let mut registry = read_local_registry()?;
+        registry.push(translation.clone());
+        write_local_registry(&registry)?;
+        self.translations.push(translation.clone());
+        Ok(translation)
+    }
+
+    /// `local-<slug>`, numbered if that id is already taken by a listed or stored translation
+    fn unique_local_id(&self, title: &str) -> Result<String, BibleError> {
+        let slug = title
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let base = if slug.is_empty() { "local".to_string() } else { format!("local-{}", slug) };
+        let registry = read_local_registry()?;
+        let mut id = base.clone();
+        let mut n = 2;
+        while self.translations.iter().chain(&registry).any(|t| t.id == id) || source_for_translation(&id)?.is_some() {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+        Ok(id)
+    }
+
+    /// Copy book files into `translations/<id>.<ext>/` and import them like a downloaded XML
+    async fn import_paratext_files(&mut self, translation_id: &str, files: &[PathBuf], kind: SourceKind) -> Result<PathBuf, BibleError> {
+        if files.is_empty() {
+            return Err(BibleError::InvalidInput("No files selected for import".to_string()));
+        }
+        // Validate before touching the translations directory
+        for file in files {
+            let text = match kind {
+                SourceKind::Usfm => paratext::parse_usfm_file(file, translation_id)?,
+                _ => paratext::parse_usx_file(file, translation_id)?,
+            };
+            if text.verses.is_empty() {
+                return Err(BibleError::UnsupportedFormat(format!("no verses found in {}", file.display())));
+            }
+        }
+
         let dir = app_data_dir()?.join("translations");
         ensure_dir(&dir).await?;
-        let dest = dir.join(format!("{}.xml", translation_id));
-        // Skip if already exists
-        if tokio::fs::try_exists(&dest).await.map_err(|e| e.to_string())? {
-            return Ok(dest);
-        }
-        let client = Client::new();
-        let resp = client.get(url).send().await.map_err(|e| e.to_string())?.error_for_status().map_err(|e| e.to_string())?;
-        let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
-        fs::write(&dest, &bytes).await.map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+        let dest = dir.join(format!("{}.{}", translation_id, kind.extension()));
+        if tokio::fs::try_exists(&dest).await.map_err(|e| BibleError::io("check", &dest, e))? {
+            fs::remove_dir_all(&dest).await.map_err(|e| BibleError::io("replace", &dest, e))?;
+        }
+        ensure_dir(&dest).await?;
+        for file in files {
+            let name = file
+                .file_name()
+                .ok_or_else(|| BibleError::InvalidInput(format!("Invalid file path {}", file.display())))?;
+            fs::copy(file, dest.join(name)).await.map_err(|e| BibleError::io("copy", file, e))?;
+        }
+
+        self.forget_cached(translation_id);
+        import_translation(translation_id)?;
         Ok(dest)
     }
 
-    pub async fn is_translation_downloaded(&self, translation_id: &str) -> Result<bool, String> {
-        let path = app_data_dir()?.join("translations").join(format!("{}.xml", translation_id));
-        Ok(tokio::fs::try_exists(path).await.map_err(|e| e.to_string())?)
+    /// Translations to offer on first run, best first (see `first_run::rank`). None once a
+    /// translation is on disk or a default has been chosen, or if nothing can be downloaded.
+    pub async fn first_run_choices(&mut self) -> Result<Option<Vec<Translation>>, BibleError> {
+        if Settings::load().default_translation.is_some() || self.has_local_translations().await? {
+            return Ok(None);
+        }
+        if self.translations.is_empty() {
+            self.load_translations().await?;
+        }
+        let downloadable: Vec<Translation> = self
+            .translations
+            .iter()
+            .filter(|t| self.remote_file(&t.id).is_some())
+            .cloned()
+            .collect();
+        if downloadable.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(first_run::rank(&downloadable, first_run::system_language().as_deref())))
     }
 
-    pub async fn ensure_default_translation(&mut self) -> Result<(), String> {
-        // Try to ensure we have at least one translation downloaded
-        // First refresh the remote index
-        if let Err(_) = self.fetch_and_cache_remote_index().await {
-            // If remote fails, we'll rely on bundled fallback in main.rs
-            return Ok(());
+    /// Remember the translation to open at startup
+    pub fn set_default_translation(&self, translation_id: &str) -> Result<(), BibleError> {
+        let mut settings = Settings::load();
+        settings.default_translation = Some(translation_id.to_string());
+        settings.save()
+    }
+
+    /// Translation to open at startup: the one the user chose, otherwise the best one on disk
+    /// for the system language, otherwise the best listed one
+    pub fn preferred_translation(&self) -> Option<Translation> {
+        if let Some(chosen) = Settings::load()
+            .default_translation
+            .and_then(|id| self.translations.iter().find(|t| t.id == id))
+        {
+            return Some(chosen.clone());
         }
-        
-        // Look for any already downloaded translations
+        let ranked = first_run::rank(&self.translations, first_run::system_language().as_deref());
+        ranked
+            .iter()
+            .find(|t| self.translation_status(&t.id) == TranslationStatus::Downloaded)
+            .or(ranked.first())
+            .cloned()
+    }
+
+    /// Whether any translation has been downloaded or imported
+    async fn has_local_translations(&self) -> Result<bool, BibleError> {
         let data_dir = app_data_dir()?.join("translations");
-        if let Ok(entries) = tokio::fs::read_dir(&data_dir).await {
-            let mut entries = entries;
-            while let Ok(Some(entry)) = entries.next_entry().await {
-                if entry.path().extension().and_then(|s| s.to_str()) == Some("xml") {
-                    // We have at least one XML file downloaded
-                    return Ok(());
-                }
+        let Ok(mut entries) = fs::read_dir(&data_dir).await else {
+            return Ok(false);
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let ext = entry.path().extension().and_then(|s| s.to_str()).map(str::to_string);
+            if SourceKind::ALL.iter().any(|k| ext.as_deref() == Some(k.extension())) {
+                return Ok(true);
             }
         }
-        
-        // If no translations downloaded, try to download the first available one
-        if let Some((id, _)) = self.hb_index_map.iter().next() {
-            let id = id.clone();
-            match self.download_translation_xml(&id).await {
-                Ok(_) => println!("Downloaded default translation: {}", id),
-                Err(e) => println!("Failed to download default translation {}: {}", id, e),
-            }
-        }
-        
-        Ok(())
+        Ok(false)
     }
 
     /// Load books for a specific translation
-    pub async fn load_books(&mut self, translation_id: &str) -> Result<Vec<Book>, String> {
+    pub async fn load_books(&mut self, translation_id: &str) -> Result<Vec<Book>, BibleError> {
+        // A damaged file would only give empty chapters; report it so it can be downloaded again
+        if let Some(e) = self.damaged.get(translation_id) {
+            return Err(e.clone());
+        }
+
         // Check cache first
         if let Some(cached_books) = self.books_cache.get(translation_id) {
             return Ok(cached_books.clone());
         }
 
-        // Prefer downloaded XML if available
-        if let Ok(true) = self.is_translation_downloaded(translation_id).await {
-            if let Ok(path) = self.xml_path_for_translation(translation_id) {
-                match Self::parse_books_from_xml(&path).await {
-                    Ok(books) if !books.is_empty() => {
-                        self.books_cache.insert(translation_id.to_string(), books.clone());
-                        return Ok(books);
-                    }
-                    Ok(_) => { /* fall through to standard books */ }
-                    Err(e) => {
-                        eprintln!("[BibleService] XML book parse failed for {}: {}. Using standard book list.", translation_id, e);
-                    }
-                }
+        // Prefer the book list of whichever source holds the translation, otherwise use the
+        // standard Protestant list (66 books) cut down to the testaments the index says it has
+        let coverage = self.translations.iter().find(|t| t.id == translation_id).and_then(|t| t.coverage);
+        let mut books = match self.source_books(translation_id).await {
+            Some(books) => books,
+            None => Self::get_standard_bible_books()
+                .into_iter()
+                .filter(|b| coverage.is_none_or(|c| c.includes(&b.testament)))
+                .collect(),
+        };
+        if let Some(names) = self
+            .translations
+            .iter()
+            .find(|t| t.id == translation_id)
+            .and_then(|t| data::localized_book_names(&t.language))
+        {
+            // Names the source gives its books come first
+            for book in books.iter_mut() {
+                book.localized_name = book.localized_name.take().or_else(|| names.get(&book.id).cloned());
             }
         }
-
-        // Use standard Bible book list (66 books)
-        let books = Self::get_standard_bible_books();
+        // What the source actually holds outranks what the index claims
+        if let Some(translation) = self.translations.iter_mut().find(|t| t.id == translation_id) {
+            translation.coverage = Some(TestamentCoverage::of(&books));
+        }
         self.books_cache.insert(translation_id.to_string(), books.clone());
         Ok(books)
     }
 
+    /// Book list from the first source holding the translation, `None` if no source has it
+    async fn source_books(&mut self, translation_id: &str) -> Option<Vec<Book>> {
+        for source in self.sources.iter_mut() {
+            match source.list_books(translation_id).await {
+                Ok(Some(books)) => return Some(books),
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("[BibleService] {} failed to list books for {}: {}", source.name(), translation_id, e);
+                }
+            }
+        }
+        None
+    }
+
     /// Load verses for a specific translation, book, and chapter
     pub async fn load_verses(
         &mut self,
         translation_id: &str,
         book_id: u32,
         chapter: u32,
-    ) -> Result<Vec<Verse>, String> {
+    ) -> Result<Vec<Verse>, BibleError> {
+        if let Some(e) = self.damaged.get(translation_id) {
+            return Err(e.clone());
+        }
+
         let cache_key = format!("{}_{}_{}",  translation_id, book_id, chapter);
         
         // Check cache first
@@ -274,299 +544,153 @@ impl BibleService {
             return Ok(cached_verses.clone());
         }
 
-        // Prefer downloaded XML if available
-        if let Ok(true) = self.is_translation_downloaded(translation_id).await {
-            if let Ok(path) = self.xml_path_for_translation(translation_id) {
-                match Self::parse_verses_from_xml(&path, translation_id, book_id, chapter).await {
-                    Ok(list) if !list.is_empty() => {
-                        self.verses_cache.insert(cache_key, list.clone());
-                        return Ok(list);
-                    }
-                    Ok(_) => { /* fall through to JSON fallback */ }
-                    Err(e) => {
-                        eprintln!("[BibleService] XML parse failed for {}: {}. Falling back to bundled JSON.", translation_id, e);
-                    }
+        let mut source_error = None;
+        for source in self.sources.iter_mut() {
+            match source.fetch_chapter(translation_id, book_id, chapter).await {
+                Ok(Some(list)) => {
+                    self.verses_cache.insert(cache_key, list.clone());
+                    return Ok(list);
                 }
+                Ok(None) => {}
+                Err(e) => source_error = Some(e),
             }
         }
 
-        // If XML parsing failed or no verses found, return error
-        // No more JSON fallback for verses - we require XML downloads
-        Err(format!("No verses found for {} book {} chapter {} - translation may need to be downloaded", translation_id, book_id, chapter))
+        // The file is there but unreadable (e.g. unsupported format): say so instead of asking for a download
+        if let Some(e) = source_error {
+            return Err(e);
+        }
+        match self.source_books(translation_id).await {
+            None => Err(BibleError::NotDownloaded(translation_id.to_string())),
+            Some(books) if !books.iter().any(|b| b.id == book_id) => Err(BibleError::UnknownBook {
+                translation_id: translation_id.to_string(),
+                book_id,
+            }),
+            Some(_) => Err(BibleError::MissingChapter { translation_id: translation_id.to_string(), book_id, chapter }),
+        }
     }
 
-    fn xml_path_for_translation(&self, translation_id: &str) -> Result<PathBuf, String> {
-        Ok(app_data_dir()?.join("translations").join(format!("{}.xml", translation_id)))
-    }
+    /// `secondary_id`'s text for a chapter of `primary_id`, renumbered to the primary's
+    /// versification so verses pair up by number. Secondary verses that fall on one primary
+    /// verse (e.g. a Psalm title and its first verse) are joined.
+    pub async fn load_parallel_verses(
+        &mut self,
+        primary_id: &str,
+        secondary_id: &str,
+        book_id: u32,
+        chapter: u32,
+    ) -> Result<Vec<Verse>, BibleError> {
+        let primary = self.versification(primary_id);
+        let secondary = self.versification(secondary_id);
+        if primary == secondary {
+            return self.load_verses(secondary_id, book_id, chapter).await;
+        }
 
-    async fn parse_verses_from_xml(
-        path: &Path,
-        translation_id: &str,
-        target_book_id: u32,
-        target_chapter: u32,
-    ) -> Result<Vec<Verse>, String> {
-        use quick_xml::events::Event;
-        use quick_xml::name::QName;
-        use quick_xml::Reader;
-        use std::fs::File;
-        use std::io::BufReader;
-
-        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
-        let mut reader = Reader::from_reader(BufReader::new(file));
-        #[allow(deprecated)]
-        {
-            // quick-xml >=0.38 uses config_mut(); some versions expose a setter, else set field
-            let cfg = reader.config_mut();
-            cfg.trim_text(true);
-        }
-
-        let mut buf = Vec::new();
-        let mut verses: Vec<Verse> = Vec::new();
-
-        // We assume OSIS-like: <verse osisID="Gen.1.1">text</verse>
-        // Map osis book code to our book_id via abbreviation from bundled books list
-        let osis_to_book_id = Self::osis_book_map()?;
-
-        let mut current_osis_id: Option<String> = None;
-        let mut collecting_text = false;
-        let mut text_acc = String::new();
-
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Eof) => break,
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                    if e.name() == QName(b"verse") {
-                        // Find osisID attribute
-                        let mut osis_id: Option<String> = None;
-                        for attr in e.attributes().with_checks(false) {
-                            if let Ok(a) = attr {
-                                if a.key == QName(b"osisID") {
-                                    if let Ok(val) = a.unescape_value() {
-                                        osis_id = Some(val.to_string());
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                        if let Some(oid) = osis_id {
-                            // Parse e.g., Gen.1.1
-                            if let Some((book_code, ch, vs)) = Self::parse_osis(&oid) {
-                                if let Some(&bid) = osis_to_book_id.get(book_code.as_str()) {
-                                    if bid == target_book_id && ch == target_chapter {
-                                        current_osis_id = Some(oid);
-                                        if matches!(e, quick_xml::events::BytesStart { .. }) {
-                                            // will collect until End(verse)
-                                            collecting_text = true;
-                                            text_acc.clear();
-                                        } else {
-                                            // Empty tag -> push immediately
-                                            verses.push(Verse {
-                                                id: format!("{}:{}:{}:{}", translation_id, bid, ch, vs),
-                                                translation_id: translation_id.to_string(),
-                                                book_id: bid,
-                                                chapter: ch,
-                                                verse: vs,
-                                                text: String::new(),
-                                            });
-                                            collecting_text = false;
-                                            current_osis_id = None;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+        // Secondary chapters the primary chapter's first and last verses fall in
+        let last = self.load_verses(primary_id, book_id, chapter).await?.iter().map(|v| v.verse).max().unwrap_or(1);
+        let (first_chapter, _) = versification::map_verse(primary, secondary, book_id, chapter, 1);
+        let (last_chapter, _) = versification::map_verse(primary, secondary, book_id, chapter, last);
+
+        let mut renumbered: std::collections::BTreeMap<u32, Verse> = std::collections::BTreeMap::new();
+        let mut first_error = None;
+        for sec_chapter in first_chapter.min(last_chapter)..=first_chapter.max(last_chapter) {
+            let list = match self.load_verses(secondary_id, book_id, sec_chapter).await {
+                Ok(list) => list,
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                    continue;
                 }
-                Ok(Event::Text(e)) => {
-                    if collecting_text {
-                        let decoded = reader.decoder().decode(e.as_ref()).unwrap_or_default();
-                        text_acc.push_str(&decoded);
-                    }
+            };
+            for verse in list {
+                let (ch, vs) = versification::map_verse(secondary, primary, book_id, verse.chapter, verse.verse);
+                if ch != chapter {
+                    continue;
                 }
-                Ok(Event::End(e)) => {
-                    if e.name() == QName(b"verse") && collecting_text {
-                        if let Some(oid) = current_osis_id.take() {
-                            if let Some((book_code, ch, vs)) = Self::parse_osis(&oid) {
-                                if let Some(&bid) = osis_to_book_id.get(book_code.as_str()) {
-                                    if bid == target_book_id && ch == target_chapter {
-                                        verses.push(Verse {
-                                            id: format!("{}:{}:{}:{}", translation_id, bid, ch, vs),
-                                            translation_id: translation_id.to_string(),
-                                            book_id: bid,
-                                            chapter: ch,
-                                            verse: vs,
-                                            text: text_acc.trim().to_string(),
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                        collecting_text = false;
-                        text_acc.clear();
+                match renumbered.get_mut(&vs) {
+                    Some(joined) => {
+                        joined.text.push(' ');
+                        joined.text.push_str(&verse.text);
+                    }
+                    None => {
+                        renumbered.insert(vs, Verse { chapter: ch, verse: vs, ..verse });
                     }
                 }
-                Err(e) => return Err(format!("XML error at pos {}: {}", reader.buffer_position(), e)),
-                _ => {}
             }
-            buf.clear();
         }
+        match first_error {
+            Some(e) if renumbered.is_empty() => Err(e),
+            _ => Ok(renumbered.into_values().collect()),
+        }
+    }
 
-        Ok(verses)
+    /// Where a bookmark falls in `translation_id`, which may number verses differently from
+    /// the translation it was made in, as (chapter, verse)
+    pub fn bookmark_position(&self, bookmark: &Bookmark, translation_id: &str) -> (u32, u32) {
+        versification::map_verse(
+            self.versification(&bookmark.translation_id),
+            self.versification(translation_id),
+            bookmark.book_id,
+            bookmark.chapter,
+            bookmark.verse,
+        )
     }
 
-    fn parse_osis(osis: &str) -> Option<(String, u32, u32)> {
-        // Expect BOOK.CHAPTER.VERSE
-        let mut parts = osis.split('.');
-        let book = parts.next()?.to_string();
-        let ch = parts.next()?.parse().ok()?;
-        let vs = parts.next()?.parse().ok()?;
-        Some((book, ch, vs))
-    }
-
-    async fn parse_books_from_xml(path: &Path) -> Result<Vec<Book>, String> {
-        use quick_xml::events::Event;
-        use quick_xml::name::QName;
-        use quick_xml::Reader;
-        use std::fs::File;
-        use std::io::BufReader;
-        use std::collections::HashMap;
-
-        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
-        let mut reader = Reader::from_reader(BufReader::new(file));
-        #[allow(deprecated)]
-        {
-            let cfg = reader.config_mut();
-            cfg.trim_text(true);
-        }
-
-        let mut buf = Vec::new();
-        let mut book_map: HashMap<String, (u32, u32)> = HashMap::new(); // osis_code -> (max_chapter, book_id)
-        let osis_to_book_id = Self::osis_book_map()?;
-
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Eof) => break,
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                    if e.name() == QName(b"verse") {
-                        // Find osisID attribute
-                        for attr in e.attributes().with_checks(false) {
-                            if let Ok(a) = attr {
-                                if a.key == QName(b"osisID") {
-                                    if let Ok(val) = a.unescape_value() {
-                                        if let Some((book_code, ch, _vs)) = Self::parse_osis(&val) {
-                                            if let Some(&bid) = osis_to_book_id.get(book_code.as_str()) {
-                                                let entry = book_map.entry(book_code).or_insert((0, bid));
-                                                entry.0 = entry.0.max(ch);
-                                            }
-                                        }
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                Err(e) => return Err(format!("XML error at pos {}: {}", reader.buffer_position(), e)),
-                _ => {}
-            }
-            buf.clear();
-        }
+    /// Bookmark a verse of `translation_id`, or remove the bookmark already on it. A bookmark
+    /// made in a translation numbered differently counts as on it when it maps to the same verse.
+    /// Saves the list and returns whether the verse is now bookmarked.
+    pub fn toggle_bookmark(
+        &self,
+        bookmarks: &mut Vec<Bookmark>,
+        translation_id: &str,
+        book_id: u32,
+        chapter: u32,
+        verse: u32,
+    ) -> Result<bool, BibleError> {
+        let bookmarked = self.toggle_bookmark_in(bookmarks, translation_id, book_id, chapter, verse);
+        write_bookmarks(bookmarks)?;
+        Ok(bookmarked)
+    }
 
-        // Convert to Book structs using standard book names
-        let standard_books = Self::get_standard_bible_books();
-        let mut found_books = Vec::new();
-        
-        for book in standard_books {
-            if let Some(&(chapter_count, _)) = book_map.get(&book.abbreviation) {
-                let mut book_with_chapters = book;
-                book_with_chapters.chapter_count = chapter_count;
-                found_books.push(book_with_chapters);
-            }
+    fn toggle_bookmark_in(&self, bookmarks: &mut Vec<Bookmark>, translation_id: &str, book_id: u32, chapter: u32, verse: u32) -> bool {
+        let before = bookmarks.len();
+        bookmarks.retain(|b| b.book_id != book_id || self.bookmark_position(b, translation_id) != (chapter, verse));
+        if bookmarks.len() != before {
+            return false;
         }
+        bookmarks.push(Bookmark {
+            id: format!("{}:{}:{}:{}", translation_id, book_id, chapter, verse),
+            user_id: None,
+            translation_id: translation_id.to_string(),
+            book_id,
+            chapter,
+            verse,
+            note: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: None,
+        });
+        true
+    }
 
-        found_books.sort_by_key(|b| b.order_index);
-        Ok(found_books)
+    fn versification(&self, translation_id: &str) -> Versification {
+        self.translations.iter().find(|t| t.id == translation_id).map(|t| t.versification).unwrap_or_default()
     }
 
+    fn parse_osis(osis: &str) -> Option<(String, u32, u32)> {
+        // Expect BOOK.CHAPTER.VERSE; ranges and whole chapters are `reference::parse_osis`
+        match reference::osis_parts(osis)? {
+            (book, Some(ch), Some(vs)) => Some((book.to_string(), ch, vs)),
+            _ => None,
+        }
+    }
+
+    /// The 66 books of the Protestant canon; `catalogue::books` has the rest
     fn get_standard_bible_books() -> Vec<Book> {
-        use crate::types::Testament;
-        
-        vec![
-            // Old Testament Books
-            Book { id: 1, name: "Genesis".to_string(), abbreviation: "Gen".to_string(), testament: Testament::OT, order_index: 1, chapter_count: 50 },
-            Book { id: 2, name: "Exodus".to_string(), abbreviation: "Exod".to_string(), testament: Testament::OT, order_index: 2, chapter_count: 40 },
-            Book { id: 3, name: "Leviticus".to_string(), abbreviation: "Lev".to_string(), testament: Testament::OT, order_index: 3, chapter_count: 27 },
-            Book { id: 4, name: "Numbers".to_string(), abbreviation: "Num".to_string(), testament: Testament::OT, order_index: 4, chapter_count: 36 },
-            Book { id: 5, name: "Deuteronomy".to_string(), abbreviation: "Deut".to_string(), testament: Testament::OT, order_index: 5, chapter_count: 34 },
-            Book { id: 6, name: "Joshua".to_string(), abbreviation: "Josh".to_string(), testament: Testament::OT, order_index: 6, chapter_count: 24 },
-            Book { id: 7, name: "Judges".to_string(), abbreviation: "Judg".to_string(), testament: Testament::OT, order_index: 7, chapter_count: 21 },
-            Book { id: 8, name: "Ruth".to_string(), abbreviation: "Ruth".to_string(), testament: Testament::OT, order_index: 8, chapter_count: 4 },
-            Book { id: 9, name: "1 Samuel".to_string(), abbreviation: "1Sam".to_string(), testament: Testament::OT, order_index: 9, chapter_count: 31 },
-            Book { id: 10, name: "2 Samuel".to_string(), abbreviation: "2Sam".to_string(), testament: Testament::OT, order_index: 10, chapter_count: 24 },
-            Book { id: 11, name: "1 Kings".to_string(), abbreviation: "1Kgs".to_string(), testament: Testament::OT, order_index: 11, chapter_count: 22 },
-            Book { id: 12, name: "2 Kings".to_string(), abbreviation: "2Kgs".to_string(), testament: Testament::OT, order_index: 12, chapter_count: 25 },
-            Book { id: 13, name: "1 Chronicles".to_string(), abbreviation: "1Chr".to_string(), testament: Testament::OT, order_index: 13, chapter_count: 29 },
-            Book { id: 14, name: "2 Chronicles".to_string(), abbreviation: "2Chr".to_string(), testament: Testament::OT, order_index: 14, chapter_count: 36 },
-            Book { id: 15, name: "Ezra".to_string(), abbreviation: "Ezra".to_string(), testament: Testament::OT, order_index: 15, chapter_count: 10 },
-            Book { id: 16, name: "Nehemiah".to_string(), abbreviation: "Neh".to_string(), testament: Testament::OT, order_index: 16, chapter_count: 13 },
-            Book { id: 17, name: "Esther".to_string(), abbreviation: "Esth".to_string(), testament: Testament::OT, order_index: 17, chapter_count: 10 },
-            Book { id: 18, name: "Job".to_string(), abbreviation: "Job".to_string(), testament: Testament::OT, order_index: 18, chapter_count: 42 },
-            Book { id: 19, name: "Psalms".to_string(), abbreviation: "Ps".to_string(), testament: Testament::OT, order_index: 19, chapter_count: 150 },
-            Book { id: 20, name: "Proverbs".to_string(), abbreviation: "Prov".to_string(), testament: Testament::OT, order_index: 20, chapter_count: 31 },
-            Book { id: 21, name: "Ecclesiastes".to_string(), abbreviation: "Eccl".to_string(), testament: Testament::OT, order_index: 21, chapter_count: 12 },
-            Book { id: 22, name: "Song of Solomon".to_string(), abbreviation: "Song".to_string(), testament: Testament::OT, order_index: 22, chapter_count: 8 },
-            Book { id: 23, name: "Isaiah".to_string(), abbreviation: "Isa".to_string(), testament: Testament::OT, order_index: 23, chapter_count: 66 },
-            Book { id: 24, name: "Jeremiah".to_string(), abbreviation: "Jer".to_string(), testament: Testament::OT, order_index: 24, chapter_count: 52 },
-            Book { id: 25, name: "Lamentations".to_string(), abbreviation: "Lam".to_string(), testament: Testament::OT, order_index: 25, chapter_count: 5 },
-            Book { id: 26, name: "Ezekiel".to_string(), abbreviation: "Ezek".to_string(), testament: Testament::OT, order_index: 26, chapter_count: 48 },
-            Book { id: 27, name: "Daniel".to_string(), abbreviation: "Dan".to_string(), testament: Testament::OT, order_index: 27, chapter_count: 12 },
-            Book { id: 28, name: "Hosea".to_string(), abbreviation: "Hos".to_string(), testament: Testament::OT, order_index: 28, chapter_count: 14 },
-            Book { id: 29, name: "Joel".to_string(), abbreviation: "Joel".to_string(), testament: Testament::OT, order_index: 29, chapter_count: 3 },
-            Book { id: 30, name: "Amos".to_string(), abbreviation: "Amos".to_string(), testament: Testament::OT, order_index: 30, chapter_count: 9 },
-            Book { id: 31, name: "Obadiah".to_string(), abbreviation: "Obad".to_string(), testament: Testament::OT, order_index: 31, chapter_count: 1 },
-            Book { id: 32, name: "Jonah".to_string(), abbreviation: "Jonah".to_string(), testament: Testament::OT, order_index: 32, chapter_count: 4 },
-            Book { id: 33, name: "Micah".to_string(), abbreviation: "Mic".to_string(), testament: Testament::OT, order_index: 33, chapter_count: 7 },
-            Book { id: 34, name: "Nahum".to_string(), abbreviation: "Nah".to_string(), testament: Testament::OT, order_index: 34, chapter_count: 3 },
-            Book { id: 35, name: "Habakkuk".to_string(), abbreviation: "Hab".to_string(), testament: Testament::OT, order_index: 35, chapter_count: 3 },
-            Book { id: 36, name: "Zephaniah".to_string(), abbreviation: "Zeph".to_string(), testament: Testament::OT, order_index: 36, chapter_count: 3 },
-            Book { id: 37, name: "Haggai".to_string(), abbreviation: "Hag".to_string(), testament: Testament::OT, order_index: 37, chapter_count: 2 },
-            Book { id: 38, name: "Zechariah".to_string(), abbreviation: "Zech".to_string(), testament: Testament::OT, order_index: 38, chapter_count: 14 },
-            Book { id: 39, name: "Malachi".to_string(), abbreviation: "Mal".to_string(), testament: Testament::OT, order_index: 39, chapter_count: 4 },
-            
-            // New Testament Books
-            Book { id: 40, name: "Matthew".to_string(), abbreviation: "Matt".to_string(), testament: Testament::NT, order_index: 40, chapter_count: 28 },
-            Book { id: 41, name: "Mark".to_string(), abbreviation: "Mark".to_string(), testament: Testament::NT, order_index: 41, chapter_count: 16 },
-            Book { id: 42, name: "Luke".to_string(), abbreviation: "Luke".to_string(), testament: Testament::NT, order_index: 42, chapter_count: 24 },
-            Book { id: 43, name: "John".to_string(), abbreviation: "John".to_string(), testament: Testament::NT, order_index: 43, chapter_count: 21 },
-            Book { id: 44, name: "Acts".to_string(), abbreviation: "Acts".to_string(), testament: Testament::NT, order_index: 44, chapter_count: 28 },
-            Book { id: 45, name: "Romans".to_string(), abbreviation: "Rom".to_string(), testament: Testament::NT, order_index: 45, chapter_count: 16 },
-            Book { id: 46, name: "1 Corinthians".to_string(), abbreviation: "1Cor".to_string(), testament: Testament::NT, order_index: 46, chapter_count: 16 },
-            Book { id: 47, name: "2 Corinthians".to_string(), abbreviation: "2Cor".to_string(), testament: Testament::NT, order_index: 47, chapter_count: 13 },
-            Book { id: 48, name: "Galatians".to_string(), abbreviation: "Gal".to_string(), testament: Testament::NT, order_index: 48, chapter_count: 6 },
-            Book { id: 49, name: "Ephesians".to_string(), abbreviation: "Eph".to_string(), testament: Testament::NT, order_index: 49, chapter_count: 6 },
-            Book { id: 50, name: "Philippians".to_string(), abbreviation: "Phil".to_string(), testament: Testament::NT, order_index: 50, chapter_count: 4 },
-            Book { id: 51, name: "Colossians".to_string(), abbreviation: "Col".to_string(), testament: Testament::NT, order_index: 51, chapter_count: 4 },
-            Book { id: 52, name: "1 Thessalonians".to_string(), abbreviation: "1Thess".to_string(), testament: Testament::NT, order_index: 52, chapter_count: 5 },
-            Book { id: 53, name: "2 Thessalonians".to_string(), abbreviation: "2Thess".to_string(), testament: Testament::NT, order_index: 53, chapter_count: 3 },
-            Book { id: 54, name: "1 Timothy".to_string(), abbreviation: "1Tim".to_string(), testament: Testament::NT, order_index: 54, chapter_count: 6 },
-            Book { id: 55, name: "2 Timothy".to_string(), abbreviation: "2Tim".to_string(), testament: Testament::NT, order_index: 55, chapter_count: 4 },
-            Book { id: 56, name: "Titus".to_string(), abbreviation: "Titus".to_string(), testament: Testament::NT, order_index: 56, chapter_count: 3 },
-            Book { id: 57, name: "Philemon".to_string(), abbreviation: "Phlm".to_string(), testament: Testament::NT, order_index: 57, chapter_count: 1 },
-            Book { id: 58, name: "Hebrews".to_string(), abbreviation: "Heb".to_string(), testament: Testament::NT, order_index: 58, chapter_count: 13 },
-            Book { id: 59, name: "James".to_string(), abbreviation: "Jas".to_string(), testament: Testament::NT, order_index: 59, chapter_count: 5 },
-            Book { id: 60, name: "1 Peter".to_string(), abbreviation: "1Pet".to_string(), testament: Testament::NT, order_index: 60, chapter_count: 5 },
-            Book { id: 61, name: "2 Peter".to_string(), abbreviation: "2Pet".to_string(), testament: Testament::NT, order_index: 61, chapter_count: 3 },
-            Book { id: 62, name: "1 John".to_string(), abbreviation: "1John".to_string(), testament: Testament::NT, order_index: 62, chapter_count: 5 },
-            Book { id: 63, name: "2 John".to_string(), abbreviation: "2John".to_string(), testament: Testament::NT, order_index: 63, chapter_count: 1 },
-            Book { id: 64, name: "3 John".to_string(), abbreviation: "3John".to_string(), testament: Testament::NT, order_index: 64, chapter_count: 1 },
-            Book { id: 65, name: "Jude".to_string(), abbreviation: "Jude".to_string(), testament: Testament::NT, order_index: 65, chapter_count: 1 },
-            Book { id: 66, name: "Revelation".to_string(), abbreviation: "Rev".to_string(), testament: Testament::NT, order_index: 66, chapter_count: 22 },
-        ]
+        catalogue::books().into_iter().filter(|b| b.canon == Canon::Protocanon).collect()
     }
 
-    fn osis_book_map() -> Result<std::collections::HashMap<String, u32>, String> {
-        // Use standard Bible book list for ID mapping and abbreviations
-        let books = Self::get_standard_bible_books();
+    fn osis_book_map() -> Result<std::collections::HashMap<String, u32>, BibleError> {
+        // Every catalogue book, so deuterocanonical books aren't dropped; abbreviations are OSIS codes
+        let books = catalogue::books();
         let mut map = std::collections::HashMap::new();
         for b in books {
             map.insert(b.abbreviation.clone(), b.id);
@@ -576,31 +700,16 @@ impl BibleService {
 
     // Note: access translations via `load_translations` return value
 
-    /// Search for verses containing the query text across all books
-    pub async fn search_verses(&mut self, translation_id: &str, query: &str) -> Result<Vec<Verse>, Box<dyn std::error::Error>> {
-        let search_query = query.to_lowercase();
-        let mut search_results = Vec::new();
-        
-        // Get all books for this translation
+    /// Search every verse of a translation, ranked by relevance
+    pub async fn search_verses(&mut self, translation_id: &str, query: &str) -> Result<SearchResult, BibleError> {
         let books = self.load_books(translation_id).await?;
-        
-        // Search through all chapters of all books (limit to first few books for performance)
-        for book in books.iter().take(5) { // Limit search to first 5 books for demo
-            for chapter in 1..=book.chapter_count.min(3) { // Limit to first 3 chapters per book
-                match self.load_verses(translation_id, book.id, chapter).await {
-                    Ok(verses) => {
-                        for verse in verses {
-                            if verse.text.to_lowercase().contains(&search_query) {
-                                search_results.push(verse);
-                            }
-                        }
-                    }
-                    Err(_) => continue, // Skip chapters that fail to load
-                }
+
+        for source in self.sources.iter_mut() {
+            if let Some(result) = source.search(translation_id, query, &books).await? {
+                return Ok(result);
             }
         }
-        
-        Ok(search_results)
+        Err(BibleError::NotDownloaded(translation_id.to_string()))
     }
 }
 
@@ -641,6 +750,10 @@ struct HbTranslation {
     testament_coverage: Option<HbTestamentCoverage>,
     #[serde(default)]
     metadata: Option<HbMetadata>,
+    #[serde(default, alias = "checksum")]
+    sha256: Option<String>,
+    #[serde(default)]
+    versification: Versification,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -689,20 +802,7 @@ struct HbEntry {
     download_url: Option<String>,
 }
 
-#[derive(Debug)]
-struct HbEntryMinimal {
-    download_url: Option<String>,
-}
-
-#[derive(Error, Debug)]
-enum FetchError {
-    #[error("network error: {0}")]
-    Network(#[from] reqwest::Error),
-    #[error("parse error: {0}")]
-    Parse(#[from] serde_json::Error),
-}
-
-fn parse_hb_entries(bytes: &[u8]) -> Result<Vec<HbEntry>, FetchError> {
+fn parse_hb_entries(bytes: &[u8]) -> Result<Vec<HbEntry>, BibleError> {
     // Try top-level array first
     if let Ok(list) = serde_json::from_slice::<Vec<HbEntry>>(bytes) {
         return Ok(list);
@@ -736,21 +836,267 @@ fn parse_hb_entries(bytes: &[u8]) -> Result<Vec<HbEntry>, FetchError> {
         }
     }
     // If all formats fail, return serde error from object attempt for context
-    let wrapped: Wrapper = serde_json::from_slice(bytes)?;
+    let wrapped: Wrapper = serde_json::from_slice(bytes).map_err(|e| BibleError::json("translations index", e))?;
     Ok(wrapped.translations)
 }
 
 // Storage helpers
-fn app_data_dir() -> Result<PathBuf, String> {
-    let proj = ProjectDirs::from("dev", "StudyBible", "StudyBible").ok_or_else(|| "Cannot determine user data directory".to_string())?;
+
+/// Size and modification time of a source file, used to detect when derived data is stale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SourceStamp {
+    len: u64,
+    modified_ms: u64,
+}
+
+impl SourceStamp {
+    /// Stamp of a file, or of a directory of book files (total size, newest modification)
+    fn of(path: &Path) -> Result<Self, BibleError> {
+        let stat = |p: &Path| std::fs::metadata(p).map_err(|e| BibleError::io("stat", p, e));
+        let modified_ms = |meta: &std::fs::Metadata| {
+            meta.modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0)
+        };
+
+        let meta = stat(path)?;
+        if !meta.is_dir() {
+            return Ok(Self { len: meta.len(), modified_ms: modified_ms(&meta) });
+        }
+        let mut stamp = Self { len: 0, modified_ms: 0 };
+        for file in paratext::book_files(path)? {
+            let meta = stat(&file)?;
+            stamp.len += meta.len();
+            stamp.modified_ms = stamp.modified_ms.max(modified_ms(&meta));
+        }
+        Ok(stamp)
+    }
+}
+
+/// How a translation's source text is stored under `translations/`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SourceKind {
+    /// `<id>.xml`, any layout `formats` understands
+    Xml,
+    /// `<id>.usfm/`, one USFM file per book
+    Usfm,
+    /// `<id>.usx/`, one USX file per book
+    Usx,
+}
+
+impl SourceKind {
+    /// Lookup order when a translation has more than one source
+    const ALL: [SourceKind; 3] = [SourceKind::Xml, SourceKind::Usfm, SourceKind::Usx];
+
+    /// Guess how a file picked for import is stored from its extension; anything else is tried as XML
+    fn for_import(path: &Path) -> Self {
+        match path.extension().and_then(|s| s.to_str()).map(|s| s.to_ascii_lowercase()).as_deref() {
+            Some("usfm") | Some("sfm") => SourceKind::Usfm,
+            Some("usx") => SourceKind::Usx,
+            _ => SourceKind::Xml,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            SourceKind::Xml => "xml",
+            SourceKind::Usfm => "usfm",
+            SourceKind::Usx => "usx",
+        }
+    }
+}
+
+/// The translation's source text inside `translations/`, if it has one
+fn source_for_translation(translation_id: &str) -> Result<Option<(PathBuf, SourceKind)>, BibleError> {
+    let dir = app_data_dir()?.join("translations");
+    Ok(SourceKind::ALL
+        .into_iter()
+        .map(|kind| (dir.join(format!("{}.{}", translation_id, kind.extension())), kind))
+        .find(|(path, _)| path.exists()))
+}
+
+/// Source of a downloaded or imported translation, with its current stamp
+fn current_source(translation_id: &str) -> Result<Option<(PathBuf, SourceKind, SourceStamp)>, BibleError> {
+    let Some((path, kind)) = source_for_translation(translation_id)? else {
+        return Ok(None);
+    };
+    let stamp = SourceStamp::of(&path)?;
+    Ok(Some((path, kind, stamp)))
+}
+
+fn index_path_for_translation(translation_id: &str) -> Result<PathBuf, BibleError> {
+    Ok(app_data_dir()?.join("translations").join(format!("{}.idx.json", translation_id)))
+}
+
+fn store_path_for_translation(translation_id: &str) -> Result<PathBuf, BibleError> {
+    Ok(app_data_dir()?.join("translations").join(format!("{}.store", translation_id)))
+}
+
+/// Size and checksum against `remote` where it lists them, then a sample of verses, importing the
+/// store first if needed. Blocking, and needs no service, so a download can run it unlocked.
+fn check_translation(translation_id: &str, remote: Option<&RemoteFile>) -> Result<(), BibleError> {
+    let (path, kind, _) = current_source(translation_id)?
+        .ok_or_else(|| BibleError::NotDownloaded(translation_id.to_string()))?;
+    if let (SourceKind::Xml, Some(remote)) = (kind, remote) {
+        verify::check_file(&path, remote)?;
+    }
+    let store = open_or_import_store(translation_id)?
+        .ok_or_else(|| BibleError::NotDownloaded(translation_id.to_string()))?;
+    verify::check_sample(&store, translation_id, &path)
+}
+
+/// Open the translation's chapter store, importing its source first if the store is missing or stale
+fn open_or_import_store(translation_id: &str) -> Result<Option<ChapterStore>, BibleError> {
+    let Some((_, _, stamp)) = current_source(translation_id)? else {
+        return Ok(None);
+    };
+    let store_path = store_path_for_translation(translation_id)?;
+    match ChapterStore::open(&store_path) {
+        Ok(store) if store.is_current(&stamp) => Ok(Some(store)),
+        _ => {
+            import_translation(translation_id)?;
+            ChapterStore::open(&store_path).map(Some)
+        }
+    }
+}
+
+/// Every file or directory under `translations/` that belongs to a translation
+fn translation_paths(translation_id: &str) -> Result<Vec<PathBuf>, BibleError> {
+    let dir = app_data_dir()?.join("translations");
+    let mut paths: Vec<PathBuf> = SourceKind::ALL
+        .iter()
+        .map(|k| dir.join(format!("{}.{}", translation_id, k.extension())))
+        .collect();
+    paths.push(store_path_for_translation(translation_id)?);
+    paths.push(index_path_for_translation(translation_id)?);
+    paths.push(downloaded_from_path(translation_id)?);
+    paths.push(dir.join(format!("{}.xml.part", translation_id)));
+    paths.push(dir.join(format!("{}.xml.part.json", translation_id)));
+    Ok(paths)
+}
+
+/// Size of a file, or of everything inside a directory; 0 if it doesn't exist
+async fn size_on_disk(path: &Path) -> u64 {
+    let Ok(meta) = fs::metadata(path).await else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    let mut total = 0;
+    if let Ok(mut entries) = fs::read_dir(path).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            total += Box::pin(size_on_disk(&entry.path())).await;
+        }
+    }
+    total
+}
+
+/// The index entry a download was made from, kept beside the file
+fn downloaded_from_path(translation_id: &str) -> Result<PathBuf, BibleError> {
+    Ok(app_data_dir()?.join("translations").join(format!("{}.xml.meta.json", translation_id)))
+}
+
+fn read_downloaded_from(translation_id: &str) -> Option<RemoteFile> {
+    let bytes = std::fs::read(downloaded_from_path(translation_id).ok()?).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn write_downloaded_from(translation_id: &str, remote: &RemoteFile) -> Result<(), BibleError> {
+    let path = downloaded_from_path(translation_id)?;
+    let bytes = serde_json::to_vec_pretty(remote).map_err(|e| BibleError::serialize(&path, e))?;
+    std::fs::write(&path, bytes).map_err(|e| BibleError::io("write", &path, e))
+}
+
+/// Record of translations whose current files passed verification
+fn verified_path() -> Result<PathBuf, BibleError> {
+    Ok(app_data_dir()?.join("translations").join("verified.json"))
+}
+
+/// One-time import: parse the source once, then write its chapter store and search index
+fn import_translation(translation_id: &str) -> Result<(), BibleError> {
+    let (source, kind, stamp) = current_source(translation_id)?
+        .ok_or_else(|| BibleError::NotDownloaded(translation_id.to_string()))?;
+    let text = match kind {
+        SourceKind::Xml => formats::parse_verses(&source, translation_id)?,
+        SourceKind::Usfm => paratext::parse_usfm_dir(&source, translation_id)?,
+        SourceKind::Usx => paratext::parse_usx_dir(&source, translation_id)?,
+    };
+    if text.verses.is_empty() {
+        return Err(BibleError::UnsupportedFormat(format!("no verses found in {}", source.display())));
+    }
+    ChapterStore::write(&store_path_for_translation(translation_id)?, &text, &stamp)?;
+    SearchIndex::build(&text.verses, stamp).save(&index_path_for_translation(translation_id)?)
+}
+
+/// A sibling of `path` to write before renaming it into place. Unique per write, so a download's
+/// import and a reader importing the same translation never share one half-written file.
+fn temp_path(path: &Path) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}-{}.tmp", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed)));
+    path.with_file_name(name)
+}
+
+fn app_data_dir() -> Result<PathBuf, BibleError> {
+    let proj = ProjectDirs::from("dev", "StudyBible", "StudyBible").ok_or_else(|| BibleError::Io {
+        action: "locate",
+        path: "user data directory".to_string(),
+        message: "no home directory found".to_string(),
+    })?;
     let dir = proj.data_dir().join("StudyBible");
     Ok(dir)
 }
 
-async fn ensure_dir(path: &Path) -> Result<(), String> {
+/// Sideloaded translations, kept next to their files so they survive index refreshes
+fn local_registry_path() -> Result<PathBuf, BibleError> {
+    Ok(app_data_dir()?.join("translations").join("local_translations.json"))
+}
+
+fn read_local_registry() -> Result<Vec<Translation>, BibleError> {
+    let path = local_registry_path()?;
+    match std::fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| BibleError::json(path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(BibleError::io("read", &path, e)),
+    }
+}
+
+fn write_local_registry(translations: &[Translation]) -> Result<(), BibleError> {
+    let path = local_registry_path()?;
+    let bytes = serde_json::to_vec_pretty(translations).map_err(|e| BibleError::serialize(&path, e))?;
+    std::fs::write(&path, bytes).map_err(|e| BibleError::io("write", &path, e))
+}
+
+fn bookmarks_path() -> Result<PathBuf, BibleError> {
+    Ok(app_data_dir()?.join("bookmarks.json"))
+}
+
+/// Saved bookmarks, each numbered as in the translation it was made in
+pub fn read_bookmarks() -> Result<Vec<Bookmark>, BibleError> {
+    let path = bookmarks_path()?;
+    match std::fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| BibleError::json(path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(BibleError::io("read", &path, e)),
+    }
+}
+
+fn write_bookmarks(bookmarks: &[Bookmark]) -> Result<(), BibleError> {
+    let path = bookmarks_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| BibleError::io("create directory", dir, e))?;
+    }
+    let bytes = serde_json::to_vec_pretty(bookmarks).map_err(|e| BibleError::serialize(&path, e))?;
+    std::fs::write(&path, bytes).map_err(|e| BibleError::io("write", &path, e))
+}
+
+async fn ensure_dir(path: &Path) -> Result<(), BibleError> {
     if let Err(e) = fs::create_dir_all(path).await {
         if e.kind() != std::io::ErrorKind::AlreadyExists {
-            return Err(format!("Failed to create directory {}: {}", path.display(), e));
+            return Err(BibleError::io("create directory", path, e));
         }
     }
     Ok(())
@@ -782,4 +1128,40 @@ fn extract_abbreviation(name: &str) -> String {
     }
 }
 
-// Removed unused ServiceManager wrapper
\ No newline at end of file
+// Removed unused ServiceManager wrapper
+#[cfg(test)]
+mod tests {
+    use super::testing::translation;
+    use super::*;
+
+    fn service() -> BibleService {
+        let mut service = BibleService::with_sources(Vec::new());
+        service.translations = vec![translation("kjv"), Translation { versification: Versification::Hebrew, ..translation("wlc") }];
+        service
+    }
+
+    #[test]
+    fn bookmarks_open_where_the_verse_is_numbered() {
+        let service = service();
+        let mut bookmarks = Vec::new();
+        // Malachi 3:19 in Hebrew numbering is 4:1 in KJV
+        assert!(service.toggle_bookmark_in(&mut bookmarks, "wlc", 39, 3, 19));
+        assert_eq!(service.bookmark_position(&bookmarks[0], "kjv"), (4, 1));
+        assert_eq!(service.bookmark_position(&bookmarks[0], "wlc"), (3, 19));
+        // Unknown translations are taken as KJV numbering
+        assert_eq!(service.bookmark_position(&bookmarks[0], "web"), (4, 1));
+    }
+
+    #[test]
+    fn bookmarking_the_same_verse_in_another_numbering_removes_it() {
+        let service = service();
+        let mut bookmarks = Vec::new();
+        assert!(service.toggle_bookmark_in(&mut bookmarks, "wlc", 29, 3, 1));
+        assert!(service.toggle_bookmark_in(&mut bookmarks, "wlc", 29, 2, 27));
+        assert_eq!(bookmarks.len(), 2);
+        // KJV Joel 2:28 is Hebrew Joel 3:1
+        assert!(!service.toggle_bookmark_in(&mut bookmarks, "kjv", 29, 2, 28));
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!((bookmarks[0].chapter, bookmarks[0].verse), (2, 27));
+    }
+}
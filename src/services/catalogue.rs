@@ -0,0 +1,171 @@
+//! Every book we can read, keyed by OSIS code: the 66 books of the Protestant canon under their
+//! usual ids, then the deuterocanonical and other books of Catholic, Orthodox and older English
+//! Bibles.
+
+use crate::types::{Book, Canon, Testament};
+use Canon::*;
+
+/// (id, OSIS code, USFM code, English name, chapters, canon)
+const BOOKS: &[(u32, &str, &str, &str, u32, Canon)] = &[
+    (1, "Gen", "GEN", "Genesis", 50, Protocanon),
+    (2, "Exod", "EXO", "Exodus", 40, Protocanon),
+    (3, "Lev", "LEV", "Leviticus", 27, Protocanon),
+    (4, "Num", "NUM", "Numbers", 36, Protocanon),
+    (5, "Deut", "DEU", "Deuteronomy", 34, Protocanon),
+    (6, "Josh", "JOS", "Joshua", 24, Protocanon),
+    (7, "Judg", "JDG", "Judges", 21, Protocanon),
+    (8, "Ruth", "RUT", "Ruth", 4, Protocanon),
+    (9, "1Sam", "1SA", "1 Samuel", 31, Protocanon),
+    (10, "2Sam", "2SA", "2 Samuel", 24, Protocanon),
+    (11, "1Kgs", "1KI", "1 Kings", 22, Protocanon),
+    (12, "2Kgs", "2KI", "2 Kings", 25, Protocanon),
+    (13, "1Chr", "1CH", "1 Chronicles", 29, Protocanon),
+    (14, "2Chr", "2CH", "2 Chronicles", 36, Protocanon),
+    (15, "Ezra", "EZR", "Ezra", 10, Protocanon),
+    (16, "Neh", "NEH", "Nehemiah", 13, Protocanon),
+    (17, "Esth", "EST", "Esther", 10, Protocanon),
+    (18, "Job", "JOB", "Job", 42, Protocanon),
+    (19, "Ps", "PSA", "Psalms", 150, Protocanon),
+    (20, "Prov", "PRO", "Proverbs", 31, Protocanon),
+    (21, "Eccl", "ECC", "Ecclesiastes", 12, Protocanon),
+    (22, "Song", "SNG", "Song of Solomon", 8, Protocanon),
+    (23, "Isa", "ISA", "Isaiah", 66, Protocanon),
+    (24, "Jer", "JER", "Jeremiah", 52, Protocanon),
+    (25, "Lam", "LAM", "Lamentations", 5, Protocanon),
+    (26, "Ezek", "EZK", "Ezekiel", 48, Protocanon),
+    (27, "Dan", "DAN", "Daniel", 12, Protocanon),
+    (28, "Hos", "HOS", "Hosea", 14, Protocanon),
+    (29, "Joel", "JOL", "Joel", 3, Protocanon),
+    (30, "Amos", "AMO", "Amos", 9, Protocanon),
+    (31, "Obad", "OBA", "Obadiah", 1, Protocanon),
+    (32, "Jonah", "JON", "Jonah", 4, Protocanon),
+    (33, "Mic", "MIC", "Micah", 7, Protocanon),
+    (34, "Nah", "NAM", "Nahum", 3, Protocanon),
+    (35, "Hab", "HAB", "Habakkuk", 3, Protocanon),
+    (36, "Zeph", "ZEP", "Zephaniah", 3, Protocanon),
+    (37, "Hag", "HAG", "Haggai", 2, Protocanon),
+    (38, "Zech", "ZEC", "Zechariah", 14, Protocanon),
+    (39, "Mal", "MAL", "Malachi", 4, Protocanon),
+    (40, "Matt", "MAT", "Matthew", 28, Protocanon),
+    (41, "Mark", "MRK", "Mark", 16, Protocanon),
+    (42, "Luke", "LUK", "Luke", 24, Protocanon),
+    (43, "John", "JHN", "John", 21, Protocanon),
+    (44, "Acts", "ACT", "Acts", 28, Protocanon),
+    (45, "Rom", "ROM", "Romans", 16, Protocanon),
+    (46, "1Cor", "1CO", "1 Corinthians", 16, Protocanon),
+    (47, "2Cor", "2CO", "2 Corinthians", 13, Protocanon),
+    (48, "Gal", "GAL", "Galatians", 6, Protocanon),
+    (49, "Eph", "EPH", "Ephesians", 6, Protocanon),
+    (50, "Phil", "PHP", "Philippians", 4, Protocanon),
+    (51, "Col", "COL", "Colossians", 4, Protocanon),
+    (52, "1Thess", "1TH", "1 Thessalonians", 5, Protocanon),
+    (53, "2Thess", "2TH", "2 Thessalonians", 3, Protocanon),
+    (54, "1Tim", "1TI", "1 Timothy", 6, Protocanon),
+    (55, "2Tim", "2TI", "2 Timothy", 4, Protocanon),
+    (56, "Titus", "TIT", "Titus", 3, Protocanon),
+    (57, "Phlm", "PHM", "Philemon", 1, Protocanon),
+    (58, "Heb", "HEB", "Hebrews", 13, Protocanon),
+    (59, "Jas", "JAS", "James", 5, Protocanon),
+    (60, "1Pet", "1PE", "1 Peter", 5, Protocanon),
+    (61, "2Pet", "2PE", "2 Peter", 3, Protocanon),
+    (62, "1John", "1JN", "1 John", 5, Protocanon),
+    (63, "2John", "2JN", "2 John", 1, Protocanon),
+    (64, "3John", "3JN", "3 John", 1, Protocanon),
+    (65, "Jude", "JUD", "Jude", 1, Protocanon),
+    (66, "Rev", "REV", "Revelation", 22, Protocanon),
+    (67, "Jdt", "JDT", "Judith", 16, Deuterocanon),
+    (68, "Wis", "WIS", "Wisdom of Solomon", 19, Deuterocanon),
+    (69, "Tob", "TOB", "Tobit", 14, Deuterocanon),
+    (70, "Sir", "SIR", "Sirach", 51, Deuterocanon),
+    (71, "Bar", "BAR", "Baruch", 6, Deuterocanon),
+    (72, "1Macc", "1MA", "1 Maccabees", 16, Deuterocanon),
+    (73, "2Macc", "2MA", "2 Maccabees", 15, Deuterocanon),
+    (74, "AddDan", "DAG", "Additions to Daniel", 14, Deuterocanon),
+    (75, "AddEsth", "ESG", "Additions to Esther", 16, Deuterocanon),
+    (76, "PrMan", "MAN", "Prayer of Manasseh", 1, Orthodox),
+    (77, "3Macc", "3MA", "3 Maccabees", 7, Orthodox),
+    (78, "4Macc", "4MA", "4 Maccabees", 18, Other),
+    (79, "EpJer", "LJE", "Letter of Jeremiah", 1, Deuterocanon),
+    (80, "1Esd", "1ES", "1 Esdras", 9, Orthodox),
+    (81, "2Esd", "2ES", "2 Esdras", 16, Other),
+    (82, "Odes", "ODA", "Odes", 14, Other),
+    (83, "PssSol", "PSS", "Psalms of Solomon", 18, Other),
+    (84, "EpLao", "LAO", "Laodiceans", 1, Other),
+    (85, "PrAzar", "S3Y", "Prayer of Azariah", 1, Deuterocanon),
+    (86, "Sus", "SUS", "Susanna", 1, Deuterocanon),
+    (87, "Bel", "BEL", "Bel and the Dragon", 1, Deuterocanon),
+    (88, "AddPs", "PS2", "Psalm 151", 1, Orthodox),
+];
+
+/// Ids in print order. The Protestant books keep their relative order; the others sit among the
+/// Old Testament books they belong with, as in Catholic and Orthodox Bibles.
+const PRINT_ORDER: &[u32] = &[
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 76, 15, 16, 80, 81, 69,
+    67, 17, 75, 72, 73, 77, 78, 18, 19, 88, 82, 20, 21, 22, 68, 70, 83, 23, 24, 25,
+    71, 79, 26, 27, 74, 85, 86, 87, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39,
+    40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 84, 52, 53, 54, 55, 56, 57, 58,
+    59, 60, 61, 62, 63, 64, 65, 66,
+];
+
+/// The whole catalogue in print order
+pub fn books() -> Vec<Book> {
+    PRINT_ORDER.iter().filter_map(|id| book(*id)).collect()
+}
+
+/// A catalogue book by id
+pub fn book(id: u32) -> Option<Book> {
+    let &(id, osis, _, name, chapter_count, canon) = BOOKS.iter().find(|b| b.0 == id)?;
+    let order_index = PRINT_ORDER.iter().position(|o| *o == id).map_or(u32::MAX, |p| p as u32 + 1);
+    Some(Book {
+        id,
+        name: name.to_string(),
+        abbreviation: osis.to_string(),
+        testament: if matches!(id, 40..=66 | 84) { Testament::NT } else { Testament::OT },
+        order_index,
+        chapter_count,
+        localized_name: None,
+        canon,
+    })
+}
+
+/// Book id for a USFM/Paratext book code such as `GEN` or `1MA`
+pub fn id_for_usfm(code: &str) -> Option<u32> {
+    let code = code.trim().to_ascii_uppercase();
+    BOOKS.iter().find(|b| b.2 == code).map(|b| b.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn print_order_lists_every_book_once() {
+        let ids: HashSet<u32> = BOOKS.iter().map(|b| b.0).collect();
+        let ordered: HashSet<u32> = PRINT_ORDER.iter().copied().collect();
+        assert_eq!(ordered.len(), PRINT_ORDER.len());
+        assert_eq!(ids, ordered);
+        assert_eq!(books().len(), BOOKS.len());
+        assert!(books().iter().enumerate().all(|(i, b)| b.order_index == i as u32 + 1));
+    }
+
+    #[test]
+    fn protestant_books_keep_their_order_and_ids() {
+        let protestant: Vec<u32> = books().iter().filter(|b| b.canon == Protocanon).map(|b| b.id).collect();
+        assert_eq!(protestant, (1..=66).collect::<Vec<_>>());
+        assert!(books().iter().filter(|b| b.testament == Testament::NT).all(|b| matches!(b.id, 40..=66 | 84)));
+    }
+
+    #[test]
+    fn codes_name_one_book_each() {
+        let osis: HashSet<&str> = BOOKS.iter().map(|b| b.1).collect();
+        let usfm: HashSet<&str> = BOOKS.iter().map(|b| b.2).collect();
+        assert_eq!(osis.len(), BOOKS.len());
+        assert_eq!(usfm.len(), BOOKS.len());
+        assert_eq!(id_for_usfm("gen"), Some(1));
+        assert_eq!(id_for_usfm(" 1MA "), Some(72));
+        assert_eq!(id_for_usfm("XYZ"), None);
+        assert_eq!(book(19).map(|b| (b.abbreviation, b.chapter_count)), Some(("Ps".to_string(), 150)));
+        assert!(book(0).is_none());
+    }
+}
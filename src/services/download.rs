@@ -0,0 +1,488 @@
+//! Streaming translation downloads: written to a `.part` file next to the destination,
+//! reported chunk by chunk, cancellable, and renamed into place only once complete.
+//! An interrupted transfer keeps its `.part` file (plus a small `.part.json` describing it) and
+//! resumes from there with an HTTP Range request, on retry or on the next attempt.
+
+use crate::error::BibleError;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
+
+/// Don't report progress more often than every this many bytes
+const PROGRESS_STEP: u64 = 64 * 1024;
+/// Transfer attempts per download before giving up, including the first
+pub(super) const MAX_ATTEMPTS: u32 = 5;
+/// Usual wait before the first retry; doubled after each further failure
+pub(super) const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+/// A stalled connection counts as dropped after this long without data
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where a translation can be downloaded from, as listed by an index. Saved next to the
+/// download so a later change to the index entry can be offered as an update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteFile {
+    pub url: String,
+    /// Other copies of the same file, tried in order if `url` fails
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// Size the index announces, if it lists one
+    pub size: Option<u64>,
+    /// Hex SHA-256 of the file, if the index lists one
+    pub sha256: Option<String>,
+    /// Edition year and status from the index metadata
+    #[serde(default)]
+    pub year: Option<u32>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl RemoteFile {
+    /// Whether `newer` describes a different file than this one. The URL is left out so moving
+    /// to another mirror doesn't count as a change.
+    pub fn is_superseded_by(&self, newer: &RemoteFile) -> bool {
+        self.size != newer.size || self.sha256 != newer.sha256 || self.year != newer.year || self.status != newer.status
+    }
+}
+
+/// Bytes received so far, against the size the index announced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DownloadProgress {
+    pub received: u64,
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// Completed share between 0 and 1, when the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|t| *t > 0)
+            .map(|t| (self.received as f64 / t as f64).min(1.0))
+    }
+}
+
+/// Stops a running download when cancelled; clones share the same state
+#[derive(Debug, Clone)]
+pub struct CancelToken(Arc<watch::Sender<bool>>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self(Arc::new(watch::Sender::new(false)))
+    }
+
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    /// Resolves once `cancel` has been called
+    pub async fn cancelled(&self) {
+        let mut rx = self.0.subscribe();
+        let _ = rx.wait_for(|c| *c).await;
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One translation file to fetch. Built by `BibleService::prepare_download` so the service
+/// doesn't have to stay locked while the bytes arrive.
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub translation_id: String,
+    pub remote: RemoteFile,
+    pub dest: PathBuf,
+    /// Wait before the first retry, normally `RETRY_BASE_DELAY`
+    pub retry_delay: Duration,
+}
+
+/// What a `.part` file is a prefix of, so it's only resumed against the same remote file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct PartMeta {
+    url: String,
+    /// ETag, or failing that Last-Modified, sent back as If-Range when resuming
+    validator: Option<String>,
+}
+
+/// How one transfer attempt went wrong, and whether trying again could help
+struct AttemptError {
+    error: BibleError,
+    retry: bool,
+}
+
+impl From<reqwest::Error> for AttemptError {
+    fn from(err: reqwest::Error) -> Self {
+        let retry = match err.status() {
+            Some(status) => status.is_server_error() || status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS,
+            None => true,
+        };
+        AttemptError { error: err.into(), retry }
+    }
+}
+
+impl AttemptError {
+    /// Local disk trouble: retrying the network won't fix it
+    fn io(action: &'static str, path: &Path, err: impl std::fmt::Display) -> Self {
+        AttemptError { error: BibleError::io(action, path, err), retry: false }
+    }
+}
+
+impl DownloadJob {
+    fn part_path(&self) -> PathBuf {
+        self.sibling("part")
+    }
+
+    fn meta_path(&self) -> PathBuf {
+        self.sibling("part.json")
+    }
+
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut name = self.dest.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(suffix);
+        self.dest.with_file_name(name)
+    }
+
+    /// Stream the file to disk, calling `on_progress` as bytes arrive. Dropped connections are
+    /// retried with backoff, resuming where they stopped, then the mirrors are tried in turn.
+    /// The destination only appears once every byte is written; a cancelled download is
+    /// discarded, a failed one kept for resuming later.
+    pub async fn run<F>(&self, cancel: &CancelToken, mut on_progress: F) -> Result<PathBuf, BibleError>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        let result = tokio::select! {
+            res = self.transfer_from_mirrors(&mut on_progress) => res,
+            _ = cancel.cancelled() => Err(BibleError::Cancelled),
+        };
+        match result {
+            Ok(()) => {
+                fs::rename(self.part_path(), &self.dest).await.map_err(|e| BibleError::io("write", &self.dest, e))?;
+                let _ = fs::remove_file(self.meta_path()).await;
+                Ok(self.dest.clone())
+            }
+            Err(BibleError::Cancelled) => {
+                let _ = fs::remove_file(self.part_path()).await;
+                let _ = fs::remove_file(self.meta_path()).await;
+                Err(BibleError::Cancelled)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Verify the finished file against its index entry and import it into a chapter store and
+    /// search index, on a blocking thread. Hand the outcome to `BibleService::finish_download`.
+    pub async fn import(&self) -> Result<(), BibleError> {
+        let translation_id = self.translation_id.clone();
+        let remote = self.remote.clone();
+        tokio::task::spawn_blocking(move || super::check_translation(&translation_id, Some(&remote)))
+            .await
+            .map_err(|e| BibleError::io("import", &self.dest, e))?
+    }
+
+    async fn transfer_from_mirrors<F>(&self, on_progress: &mut F) -> Result<(), BibleError>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        // Offsets count bytes as stored, so the body must arrive exactly as the server holds it
+        let client = Client::builder().read_timeout(READ_TIMEOUT).no_gzip().no_brotli().no_deflate().build()?;
+        let mut result = self.transfer_with_retries(&client, &self.remote.url, on_progress).await;
+        for mirror in &self.remote.mirrors {
+            let Err(e) = &result else { break };
+            eprintln!("[BibleService] Download of {} failed ({}), trying {}", self.translation_id, e, mirror);
+            result = self.transfer_with_retries(&client, mirror, on_progress).await;
+        }
+        result
+    }
+
+    async fn transfer_with_retries<F>(&self, client: &Client, url: &str, on_progress: &mut F) -> Result<(), BibleError>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        let mut delay = self.retry_delay;
+        let mut attempt = 1;
+        loop {
+            match self.transfer(client, url, on_progress).await {
+                Ok(()) => return Ok(()),
+                Err(e) if e.retry && attempt < MAX_ATTEMPTS => {
+                    eprintln!(
+                        "[BibleService] Download of {} interrupted ({}), retry {} of {} in {:?}",
+                        self.translation_id, e.error, attempt, MAX_ATTEMPTS - 1, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(RETRY_MAX_DELAY);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.error),
+            }
+        }
+    }
+
+    /// One request: resume the part file if it belongs to this URL, otherwise start over
+    async fn transfer<F>(&self, client: &Client, url: &str, on_progress: &mut F) -> Result<(), AttemptError>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        let part = self.part_path();
+        let meta = self.read_meta().await;
+        let mut offset = match fs::metadata(&part).await {
+            Ok(m) if meta.as_ref().is_some_and(|m| m.url == url) => m.len(),
+            _ => 0,
+        };
+
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+            if let Some(validator) = meta.as_ref().and_then(|m| m.validator.clone()) {
+                request = request.header(IF_RANGE, validator);
+            }
+        }
+        let resp = request.send().await?;
+
+        if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+            // Either the part is already complete or it no longer matches; the announced size decides
+            if self.remote.size == Some(offset) {
+                return Ok(());
+            }
+            let _ = fs::remove_file(&part).await;
+            return Err(AttemptError { error: BibleError::Network("resume rejected by server".to_string()), retry: true });
+        }
+        let mut resp = resp.error_for_status()?;
+
+        let partial = resp.status() == StatusCode::PARTIAL_CONTENT;
+        if partial && content_range_start(&resp) != Some(offset) {
+            // A slice from elsewhere in the file can't be appended; start over without a range
+            drop(resp);
+            let _ = fs::remove_file(&part).await;
+            let _ = fs::remove_file(self.meta_path()).await;
+            return Err(AttemptError { error: BibleError::Network("server resumed at the wrong offset".to_string()), retry: true });
+        }
+        let resumed = partial && offset > 0;
+        if !resumed {
+            // Fresh start: the server ignored the range, or the file changed since the part was written
+            offset = 0;
+            let validator = header(&resp, ETAG).or_else(|| header(&resp, LAST_MODIFIED));
+            self.write_meta(&PartMeta { url: url.to_string(), validator }).await?;
+        }
+        let total = content_range_total(&resp)
+            .or_else(|| resp.content_length().map(|len| offset + len))
+            .or(self.remote.size);
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&part)
+            .await
+            .map_err(|e| AttemptError::io("create", &part, e))?;
+
+        let mut progress = DownloadProgress { received: offset, total };
+        let mut reported = offset;
+        on_progress(progress);
+        let streamed = async {
+            while let Some(chunk) = resp.chunk().await? {
+                file.write_all(&chunk).await.map_err(|e| AttemptError::io("write", &part, e))?;
+                progress.received += chunk.len() as u64;
+                if progress.received - reported >= PROGRESS_STEP {
+                    reported = progress.received;
+                    on_progress(progress);
+                }
+            }
+            Ok::<(), AttemptError>(())
+        }
+        .await;
+        // Keep whatever arrived before a failure; the next attempt resumes after it
+        file.flush().await.map_err(|e| AttemptError::io("write", &part, e))?;
+        file.sync_all().await.map_err(|e| AttemptError::io("write", &part, e))?;
+        streamed?;
+        on_progress(progress);
+
+        match total {
+            Some(total) if progress.received < total => Err(AttemptError {
+                error: BibleError::Network(format!("connection closed after {} of {} bytes", progress.received, total)),
+                retry: true,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    async fn read_meta(&self) -> Option<PartMeta> {
+        let bytes = fs::read(self.meta_path()).await.ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    async fn write_meta(&self, meta: &PartMeta) -> Result<(), AttemptError> {
+        let path = self.meta_path();
+        let bytes = serde_json::to_vec(meta).map_err(|e| AttemptError { error: BibleError::serialize(&path, e), retry: false })?;
+        fs::write(&path, bytes).await.map_err(|e| AttemptError::io("write", &path, e))
+    }
+}
+
+fn header(resp: &Response, name: reqwest::header::HeaderName) -> Option<String> {
+    resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string)
+}
+
+/// `Content-Range: bytes 100-199/1000` → start 100
+fn content_range_start(resp: &Response) -> Option<u64> {
+    let value = header(resp, CONTENT_RANGE)?;
+    let range = value.strip_prefix("bytes ")?;
+    range.split(['-', '/']).next()?.trim().parse().ok()
+}
+
+/// `Content-Range: bytes 100-199/1000` → total 1000 (absent for `*`)
+fn content_range_total(resp: &Response) -> Option<u64> {
+    let value = header(resp, CONTENT_RANGE)?;
+    value.rsplit('/').next()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    /// What the stand-in server sends on one connection: status line and headers, then a body
+    /// that may stop short of the `Content-Length` it announced
+    pub(crate) struct Reply {
+        pub head: String,
+        pub body: Vec<u8>,
+    }
+
+    impl Reply {
+        pub(crate) fn new(status: &str, headers: &[(&str, String)], body: &[u8]) -> Self {
+            let mut head = format!("HTTP/1.1 {}\r\nConnection: close\r\n", status);
+            for (name, value) in headers {
+                head.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            head.push_str("\r\n");
+            Reply { head, body: body.to_vec() }
+        }
+    }
+
+    /// A local HTTP server answering one connection per reply, in order, then closing it. Returns
+    /// the URL of `/bible.xml` and the request heads it received, lowercased.
+    pub(crate) async fn serve(replies: Vec<Reply>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/bible.xml", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            for reply in replies {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let mut head = Vec::new();
+                let mut byte = [0u8; 1];
+                while !head.ends_with(b"\r\n\r\n") && socket.read(&mut byte).await.is_ok_and(|n| n == 1) {
+                    head.push(byte[0]);
+                }
+                seen.lock().unwrap().push(String::from_utf8_lossy(&head).to_lowercase());
+                // The client may hang up first, e.g. after rejecting the reply
+                let _ = socket.write_all(reply.head.as_bytes()).await;
+                let _ = socket.write_all(&reply.body).await;
+            }
+        });
+        (url, requests)
+    }
+
+    pub(crate) fn file_bytes(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// A job downloading into its own temp directory, retrying quickly so failure tests stay fast
+    pub(crate) fn job(name: &str, url: &str, size: usize) -> DownloadJob {
+        let dir = super::super::testing::scratch_path(&format!("download-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        DownloadJob {
+            translation_id: name.to_string(),
+            remote: RemoteFile {
+                url: url.to_string(),
+                mirrors: Vec::new(),
+                size: Some(size as u64),
+                sha256: None,
+                year: None,
+                status: None,
+            },
+            dest: dir.join(format!("{}.xml", name)),
+            retry_delay: Duration::from_millis(10),
+        }
+    }
+
+    const LEN: usize = 100_000;
+    const CUT: usize = 40_000;
+
+    /// The first reply announces the whole file but the connection drops after `CUT` bytes
+    fn dropped_after_cut(file: &[u8]) -> Reply {
+        Reply::new(
+            "200 OK",
+            &[("Content-Length", LEN.to_string()), ("ETag", "\"v1\"".to_string())],
+            &file[..CUT],
+        )
+    }
+
+    #[tokio::test]
+    async fn dropped_connection_resumes_with_range() {
+        let file = file_bytes(LEN);
+        let (url, requests) = serve(vec![
+            dropped_after_cut(&file),
+            Reply::new(
+                "206 Partial Content",
+                &[
+                    ("Content-Length", (LEN - CUT).to_string()),
+                    ("Content-Range", format!("bytes {}-{}/{}", CUT, LEN - 1, LEN)),
+                ],
+                &file[CUT..],
+            ),
+        ])
+        .await;
+        let job = job("resume", &url, LEN);
+
+        let dest = job.run(&CancelToken::new(), |_| {}).await.unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), file);
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].contains("range:"));
+        assert!(requests[1].contains(&format!("range: bytes={}-", CUT)));
+        assert!(requests[1].contains("if-range: \"v1\""));
+        assert!(requests.iter().all(|r| !r.contains("accept-encoding: gzip")));
+        assert!(!job.part_path().exists() && !job.meta_path().exists());
+    }
+
+    #[tokio::test]
+    async fn resume_at_the_wrong_offset_starts_over() {
+        let file = file_bytes(LEN);
+        let (url, requests) = serve(vec![
+            dropped_after_cut(&file),
+            // Answers the range request with a slice from further on
+            Reply::new(
+                "206 Partial Content",
+                &[
+                    ("Content-Length", (LEN - 60_000).to_string()),
+                    ("Content-Range", format!("bytes 60000-{}/{}", LEN - 1, LEN)),
+                ],
+                &file[60_000..],
+            ),
+            Reply::new("200 OK", &[("Content-Length", LEN.to_string())], &file),
+        ])
+        .await;
+        let job = job("wrong-offset", &url, LEN);
+
+        let dest = job.run(&CancelToken::new(), |_| {}).await.unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), file);
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[1].contains("range:"));
+        assert!(!requests[2].contains("range:"));
+    }
+}
@@ -0,0 +1,110 @@
+//! Picking a first translation: the system language first, then bundled translations, then the
+//! index's `priority` order.
+
+use crate::data;
+use crate::types::Translation;
+use std::cmp::Reverse;
+
+/// Primary language of the system locale, e.g. "ta" for `ta_IN.UTF-8`. The locale variables
+/// come first; on Windows, where they're rarely set, the user's locale (`ta-IN`) follows. None
+/// for the C/POSIX locale or when no locale is found.
+pub fn system_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .or_else(user_locale)
+        .and_then(|value| language_of_locale(&value))
+}
+
+#[cfg(windows)]
+fn user_locale() -> Option<String> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserDefaultLocaleName(name: *mut u16, len: i32) -> i32;
+    }
+    // LOCALE_NAME_MAX_LENGTH
+    let mut name = [0u16; 85];
+    // SAFETY: the buffer is as long as we say; the call writes at most that many units
+    let len = unsafe { GetUserDefaultLocaleName(name.as_mut_ptr(), name.len() as i32) };
+    // The length includes the terminating null; 0 means failure
+    (len > 1).then(|| String::from_utf16_lossy(&name[..len as usize - 1]))
+}
+
+#[cfg(not(windows))]
+fn user_locale() -> Option<String> {
+    None
+}
+
+fn language_of_locale(locale: &str) -> Option<String> {
+    let language = locale.split(['_', '-', '.', '@']).next()?.to_lowercase();
+    match language.as_str() {
+        "" | "c" | "posix" => None,
+        _ => Some(language),
+    }
+}
+
+/// Whether a translation is in `language`. Indexes write languages as two- or three-letter
+/// codes or as names ("ta", "tam", "tamil"), so both sides go through `data::language_code`.
+pub fn speaks(translation: &Translation, language: &str) -> bool {
+    data::language_code(&translation.language) == data::language_code(language)
+}
+
+/// `translations` in the order to offer them, best first: those in `language`, then bundled
+/// ones, then by `priority` (1 is highest; 0 means unranked), then by name
+pub fn rank(translations: &[Translation], language: Option<&str>) -> Vec<Translation> {
+    let mut ranked = translations.to_vec();
+    ranked.sort_by_key(|t| {
+        (
+            Reverse(language.is_some_and(|l| speaks(t, l))),
+            Reverse(t.bundled),
+            if t.priority == 0 { u32::MAX } else { t.priority },
+            t.name.clone(),
+        )
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translation(id: &str, language: &str, bundled: bool, priority: u32) -> Translation {
+        Translation { language: language.to_string(), bundled, priority, ..super::super::testing::translation(id) }
+    }
+
+    #[test]
+    fn locale_language() {
+        assert_eq!(language_of_locale("ta_IN.UTF-8").as_deref(), Some("ta"));
+        assert_eq!(language_of_locale("en-GB").as_deref(), Some("en"));
+        assert_eq!(language_of_locale("C.UTF-8"), None);
+        assert_eq!(language_of_locale("POSIX"), None);
+    }
+
+    #[test]
+    fn codes_and_names_match_the_locale() {
+        for language in ["ta", "tam", "Tamil", "tamil"] {
+            assert!(speaks(&translation("tamil", language, false, 0), "ta"), "{}", language);
+        }
+        for language in ["en", "eng", "English"] {
+            assert!(speaks(&translation("kjv", language, false, 0), "en"), "{}", language);
+        }
+        assert!(!speaks(&translation("kjv", "english", false, 0), "ta"));
+        // Estonian isn't Spanish just because its code starts with "es"
+        assert!(!speaks(&translation("est", "est", false, 0), "es"));
+    }
+
+    #[test]
+    fn ranks_language_then_bundled_then_priority() {
+        let translations = [
+            translation("web", "en", false, 2),
+            translation("asv", "en", false, 0),
+            translation("kjv", "eng", true, 1),
+            translation("tamil", "tamil", false, 5),
+        ];
+        let ids = |language| rank(&translations, language).into_iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(Some("ta")), ["tamil", "kjv", "web", "asv"]);
+        assert_eq!(ids(Some("en")), ["kjv", "web", "asv", "tamil"]);
+        assert_eq!(ids(None), ["kjv", "web", "tamil", "asv"]);
+    }
+}
@@ -0,0 +1,578 @@
+//! Readers for the XML layouts translations are distributed in
+
+use super::{catalogue, reference, BibleService};
+use crate::error::BibleError;
+use crate::types::Verse;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use quick_xml::name::QName;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Title and language a file declares about itself
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileInfo {
+    pub title: Option<String>,
+    pub language: Option<String>,
+}
+
+/// A translation as read from its files: verses in source order, plus whatever names the files
+/// give their books (in the translation's language, keyed by book id)
+#[derive(Debug, Clone, Default)]
+pub struct ParsedText {
+    pub verses: Vec<Verse>,
+    pub book_names: HashMap<u32, String>,
+}
+
+impl ParsedText {
+    /// Append another file's text; names already known are kept
+    pub fn extend(&mut self, other: ParsedText) {
+        self.verses.extend(other.verses);
+        for (book_id, name) in other.book_names {
+            self.book_names.entry(book_id).or_insert(name);
+        }
+    }
+}
+
+/// An XML layout translations are distributed in: how to recognise it and read it
+pub trait BibleFormat: Sync {
+    /// Whether a document whose root and first child element have these (lowercased) names is in this layout
+    fn matches(&self, root: &[u8], first_child: &[u8]) -> bool;
+
+    /// Every verse in the file, in document order, and the book names it declares
+    fn parse(&self, path: &Path, translation_id: &str) -> Result<ParsedText, BibleError>;
+
+    /// Metadata declared inside the file, for layouts that carry any
+    fn info(&self, _path: &Path) -> Option<FileInfo> {
+        None
+    }
+}
+
+/// Supported layouts, tried in order during detection
+pub static FORMATS: &[&dyn BibleFormat] = &[&Osis, &Beblia, &Zefania, &OpenSong];
+
+/// Sniff the layout from the root element and its first child
+pub fn detect(path: &Path) -> Result<&'static dyn BibleFormat, BibleError> {
+    let mut reader = open_reader(path)?;
+    let mut buf = Vec::new();
+    let mut root: Option<Vec<u8>> = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let name = e.local_name().as_ref().to_ascii_lowercase();
+                match &root {
+                    None => root = Some(name),
+                    Some(root) => {
+                        return FORMATS
+                            .iter()
+                            .copied()
+                            .find(|f| f.matches(root, &name))
+                            .ok_or_else(|| BibleError::UnsupportedFormat(format!("unrecognised XML layout in {}", path.display())));
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(BibleError::parse_at(path, reader.buffer_position(), e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+    Err(BibleError::UnsupportedFormat(format!("no XML content in {}", path.display())))
+}
+
+/// Parse every verse of a translation file, whatever supported layout it uses
+pub fn parse_verses(path: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+    detect(path)?.parse(path, translation_id)
+}
+
+/// `<osis>…<verse osisID="Gen.1.1">`
+struct Osis;
+
+impl BibleFormat for Osis {
+    fn matches(&self, root: &[u8], _first_child: &[u8]) -> bool {
+        root == b"osis"
+    }
+
+    /// Handles both container `<verse osisID>text</verse>` and milestone `sID`/`eID` verses
+    fn parse(&self, path: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+        let mut reader = open_reader(path)?;
+        let mut buf = Vec::new();
+        let mut out = VerseCollector::new(translation_id);
+
+        // Map osis book code to our book_id via abbreviation from bundled books list
+        let osis_to_book_id = BibleService::osis_book_map()?;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Eof) => break,
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name() == QName(b"verse") => {
+                    // Milestone end (<verse eID="Gen.1.1"/>) closes the verse; a new start
+                    // also closes any milestone verse left unterminated
+                    out.finish();
+                    if attr(&e, b"eID").is_none() {
+                        if let Some((book_code, ch, vs)) = attr(&e, b"osisID").as_deref().and_then(BibleService::parse_osis) {
+                            if let Some(&bid) = osis_to_book_id.get(book_code.as_str()) {
+                                out.start(bid, ch, vs);
+                            }
+                        }
+                    }
+                }
+                Ok(Event::End(e)) if e.name() == QName(b"verse") => out.finish(),
+                Ok(Event::Text(e)) => out.push_text(&e.decode().unwrap_or_default()),
+                Ok(Event::GeneralRef(e)) => out.push_ref(&e),
+                Err(e) => return Err(BibleError::parse_at(path, reader.buffer_position(), e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(out.into_text())
+    }
+
+    fn info(&self, path: &Path) -> Option<FileInfo> {
+        header_info(path, b"title", b"language", b"div")
+    }
+}
+
+/// Beblia / Holy-Bible layout served by HB_index:
+/// `<bible><testament><book number><chapter number><verse number>`
+struct Beblia;
+
+impl BibleFormat for Beblia {
+    fn matches(&self, root: &[u8], first_child: &[u8]) -> bool {
+        root == b"bible" && matches!(first_child, b"testament" | b"book")
+    }
+
+    /// `number` is taken as a catalogue book id; books with numbers we don't know are skipped
+    fn parse(&self, path: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+        let known_ids: Vec<u32> = catalogue::books().iter().map(|b| b.id).collect();
+        parse_numbered(path, translation_id, [b"book", b"chapter", b"verse"], b"number", None, |book| {
+            book.parse().ok().filter(|n| known_ids.contains(n))
+        })
+    }
+
+    fn info(&self, path: &Path) -> Option<FileInfo> {
+        let mut reader = open_reader(path).ok()?;
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    return Some(FileInfo { title: attr(&e, b"translation"), language: attr(&e, b"language") });
+                }
+                Ok(Event::Eof) | Err(_) => return None,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+}
+
+/// Zefania XML: `<XMLBIBLE><BIBLEBOOK bnumber><CHAPTER cnumber><VERS vnumber>`
+struct Zefania;
+
+/// Zefania numbers the 66 Protestant books as the catalogue does. Its numbers past 66 don't
+/// line up with catalogue ids, so any other book is recognised by its short or full name instead,
+/// and left out if neither names a book outside those 66.
+fn zefania_book_id(bnumber: Option<u32>, bsname: Option<&str>, bname: Option<&str>) -> Option<u32> {
+    match bnumber {
+        Some(n @ 1..=66) => Some(n),
+        _ => [bsname, bname]
+            .into_iter()
+            .flatten()
+            .find_map(|name| reference::find_book(name).map(|b| b.id).filter(|id| *id > 66)),
+    }
+}
+
+impl BibleFormat for Zefania {
+    fn matches(&self, root: &[u8], _first_child: &[u8]) -> bool {
+        root == b"xmlbible"
+    }
+
+    /// Books are mapped by `zefania_book_id`. `bname` is kept as the book's name; captions, notes
+    /// and cross references are left out of the verse text.
+    fn parse(&self, path: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+        let mut reader = open_reader(path)?;
+        let mut buf = Vec::new();
+        let mut out = VerseCollector::new(translation_id);
+        let mut book: Option<u32> = None;
+        let mut chapter: Option<u32> = None;
+        // Footnotes and cross references nested inside a verse
+        let mut note_depth = 0u32;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Eof) => break,
+                Ok(Event::Start(e)) => match e.local_name().as_ref().to_ascii_uppercase().as_slice() {
+                    b"BIBLEBOOK" => {
+                        let name = attr(&e, b"bname");
+                        book = zefania_book_id(number_attr(&e, b"bnumber"), attr(&e, b"bsname").as_deref(), name.as_deref());
+                        if let (Some(bid), Some(name)) = (book, name) {
+                            out.name_book(bid, &name);
+                        }
+                    }
+                    b"CHAPTER" => chapter = number_attr(&e, b"cnumber"),
+                    b"VERS" => {
+                        if let (Some(bid), Some(ch), Some(vs)) = (book, chapter, number_attr(&e, b"vnumber")) {
+                            out.start(bid, ch, vs);
+                        }
+                    }
+                    b"NOTE" | b"XREF" => note_depth += 1,
+                    _ => {}
+                },
+                Ok(Event::End(e)) => match e.local_name().as_ref().to_ascii_uppercase().as_slice() {
+                    b"VERS" => out.finish(),
+                    b"NOTE" | b"XREF" => note_depth = note_depth.saturating_sub(1),
+                    _ => {}
+                },
+                Ok(Event::Text(e)) if note_depth == 0 => out.push_text(&e.decode().unwrap_or_default()),
+                Ok(Event::GeneralRef(e)) if note_depth == 0 => out.push_ref(&e),
+                Err(e) => return Err(BibleError::parse_at(path, reader.buffer_position(), e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(out.into_text())
+    }
+
+    fn info(&self, path: &Path) -> Option<FileInfo> {
+        header_info(path, b"title", b"language", b"biblebook")
+    }
+}
+
+/// OpenSong: `<bible><b n><c n><v n>`. Books are named rather than numbered.
+struct OpenSong;
+
+impl BibleFormat for OpenSong {
+    fn matches(&self, root: &[u8], first_child: &[u8]) -> bool {
+        root == b"bible" && first_child == b"b"
+    }
+
+    /// Books are matched by English name or abbreviation, else taken to be the book after the
+    /// previous one in the Protestant order, so files holding only some books still line up
+    fn parse(&self, path: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+        let all_books = catalogue::books();
+        let standard_books = BibleService::get_standard_bible_books();
+        let mut previous: Option<u32> = None;
+        parse_numbered(path, translation_id, [b"b", b"c", b"v"], b"n", Some(b"n"), |name| {
+            let name = name.trim();
+            let id = all_books
+                .iter()
+                .find(|b| b.name.eq_ignore_ascii_case(name) || b.abbreviation.eq_ignore_ascii_case(name))
+                .map(|b| b.id)
+                .or_else(|| {
+                    let next = previous.map_or(0, |id| standard_books.iter().position(|b| b.id == id).map_or(0, |i| i + 1));
+                    standard_books.get(next).map(|b| b.id)
+                });
+            previous = id.or(previous);
+            id
+        })
+    }
+}
+
+/// Shared reader for layouts with book/chapter/verse elements carrying their number in one attribute.
+/// `book_id` maps the book element's attribute value to our book id; `name_key` is the book
+/// element's attribute holding its name, for layouts that have one.
+fn parse_numbered<F>(
+    path: &Path,
+    translation_id: &str,
+    [book_tag, chapter_tag, verse_tag]: [&[u8]; 3],
+    number_key: &[u8],
+    name_key: Option<&[u8]>,
+    mut book_id: F,
+) -> Result<ParsedText, BibleError>
+where
+    F: FnMut(&str) -> Option<u32>,
+{
+    let mut reader = open_reader(path)?;
+    let mut buf = Vec::new();
+    let mut out = VerseCollector::new(translation_id);
+    let mut book: Option<u32> = None;
+    let mut chapter: Option<u32> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let name = e.local_name();
+                if name.as_ref() == book_tag {
+                    out.finish();
+                    book = attr(&e, number_key).and_then(|v| book_id(&v));
+                    if let (Some(bid), Some(name)) = (book, name_key.and_then(|key| attr(&e, key))) {
+                        out.name_book(bid, &name);
+                    }
+                    chapter = None;
+                } else if name.as_ref() == chapter_tag {
+                    out.finish();
+                    chapter = number_attr(&e, number_key);
+                } else if name.as_ref() == verse_tag {
+                    out.finish();
+                    if let (Some(bid), Some(ch), Some(vs)) = (book, chapter, number_attr(&e, number_key)) {
+                        out.start(bid, ch, vs);
+                    }
+                }
+            }
+            Ok(Event::End(e)) if e.local_name().as_ref() == verse_tag => out.finish(),
+            Ok(Event::Text(e)) => out.push_text(&e.decode().unwrap_or_default()),
+            Ok(Event::GeneralRef(e)) => out.push_ref(&e),
+            Err(e) => return Err(BibleError::parse_at(path, reader.buffer_position(), e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(out.into_text())
+}
+
+/// Read the text of the first `title`/`language` elements, stopping once the body (`stop_at`) begins
+fn header_info(path: &Path, title_tag: &[u8], language_tag: &[u8], stop_at: &[u8]) -> Option<FileInfo> {
+    let mut reader = open_reader(path).ok()?;
+    let mut buf = Vec::new();
+    let mut info = FileInfo::default();
+    // Which field the next text event belongs to
+    let mut reading: Option<bool> = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let name = e.local_name().as_ref().to_ascii_lowercase();
+                if name == stop_at {
+                    break;
+                } else if name == title_tag && info.title.is_none() {
+                    reading = Some(true);
+                } else if name == language_tag && info.language.is_none() {
+                    reading = Some(false);
+                }
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.decode().unwrap_or_default().trim().to_string();
+                match reading.take() {
+                    Some(true) if !text.is_empty() => info.title = Some(text),
+                    Some(false) if !text.is_empty() => info.language = Some(text),
+                    _ => {}
+                }
+            }
+            Ok(Event::End(_)) => reading = None,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    (info != FileInfo::default()).then_some(info)
+}
+
+fn open_reader(path: &Path) -> Result<Reader<BufReader<File>>, BibleError> {
+    let file = File::open(path).map_err(|e| BibleError::io("open", path, e))?;
+    // Whitespace between inline elements is significant, so text is normalised per verse instead of trimmed
+    Ok(Reader::from_reader(BufReader::new(file)))
+}
+
+fn attr(e: &BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .with_checks(false)
+        .flatten()
+        .find(|a| a.key == QName(key))
+        .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()))
+}
+
+fn number_attr(e: &BytesStart, key: &[u8]) -> Option<u32> {
+    attr(e, key).and_then(|v| v.trim().parse().ok())
+}
+
+/// Accumulates the text of the verse currently open and turns finished verses into `Verse`s
+struct VerseCollector<'a> {
+    translation_id: &'a str,
+    /// (book_id, chapter, verse) of the verse whose text is being collected
+    current: Option<(u32, u32, u32)>,
+    text: String,
+    verses: Vec<Verse>,
+    book_names: HashMap<u32, String>,
+}
+
+impl<'a> VerseCollector<'a> {
+    fn new(translation_id: &'a str) -> Self {
+        Self { translation_id, current: None, text: String::new(), verses: Vec::new(), book_names: HashMap::new() }
+    }
+
+    /// Record the name the file gives a book; the first one wins
+    fn name_book(&mut self, book_id: u32, name: &str) {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !name.is_empty() {
+            self.book_names.entry(book_id).or_insert(name);
+        }
+    }
+
+    fn start(&mut self, book_id: u32, chapter: u32, verse: u32) {
+        self.finish();
+        self.current = Some((book_id, chapter, verse));
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.current.is_some() {
+            self.text.push_str(text);
+        }
+    }
+
+    fn push_ref(&mut self, e: &BytesRef) {
+        if self.current.is_none() {
+            return;
+        }
+        if let Ok(Some(ch)) = e.resolve_char_ref() {
+            self.text.push(ch);
+        } else if let Some(s) = e.decode().ok().and_then(|name| resolve_predefined_entity(&name)) {
+            self.text.push_str(s);
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some((bid, ch, vs)) = self.current.take() {
+            self.verses.push(Verse {
+                id: format!("{}:{}:{}:{}", self.translation_id, bid, ch, vs),
+                translation_id: self.translation_id.to_string(),
+                book_id: bid,
+                chapter: ch,
+                verse: vs,
+                text: self.text.split_whitespace().collect::<Vec<_>>().join(" "),
+            });
+        }
+        self.text.clear();
+    }
+
+    fn into_text(mut self) -> ParsedText {
+        self.finish();
+        ParsedText { verses: self.verses, book_names: self.book_names }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::scratch_path;
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write(name: &str, xml: &str) -> PathBuf {
+        let path = scratch_path(&format!("{}.xml", name));
+        std::fs::write(&path, xml).unwrap();
+        path
+    }
+
+    /// (book, chapter, verse, text)
+    type Row = (u32, u32, u32, String);
+
+    /// Every verse in `xml` and the book names it gives, read through layout detection
+    fn parse(name: &str, xml: &str) -> (Vec<Row>, HashMap<u32, String>) {
+        let path = write(name, xml);
+        let text = parse_verses(&path, "t").unwrap();
+        let _ = std::fs::remove_file(&path);
+        let rows = text.verses.into_iter().map(|v| (v.book_id, v.chapter, v.verse, v.text)).collect();
+        (rows, text.book_names)
+    }
+
+    fn row(book: u32, chapter: u32, verse: u32, text: &str) -> Row {
+        (book, chapter, verse, text.to_string())
+    }
+
+    #[test]
+    fn osis_container_and_milestone_verses() {
+        let (rows, _) = parse(
+            "osis",
+            r#"<osis><osisText><header><work><title>KJV</title></work></header>
+            <div type="book" osisID="Gen"><chapter osisID="Gen.1">
+              <verse osisID="Gen.1.1">In the   beginning</verse>
+              <verse sID="Gen.1.2" osisID="Gen.1.2"/>And the earth &amp; the <w>deep</w>.<verse eID="Gen.1.2"/>
+            </chapter></div>
+            <div type="book" osisID="Tob"><chapter osisID="Tob.1"><verse osisID="Tob.1.1">Tobit</verse></chapter></div>
+            <div type="book" osisID="Xyz"><chapter osisID="Xyz.1"><verse osisID="Xyz.1.1">Unknown</verse></chapter></div>
+            </osisText></osis>"#,
+        );
+        let tobit = catalogue::id_for_usfm("TOB").unwrap();
+        assert_eq!(
+            rows,
+            [row(1, 1, 1, "In the beginning"), row(1, 1, 2, "And the earth & the deep."), row(tobit, 1, 1, "Tobit")]
+        );
+    }
+
+    #[test]
+    fn beblia_numbers_and_header() {
+        let xml = r#"<bible translation="King James Version" language="English">
+            <testament name="Old"><book number="1"><chapter number="1">
+              <verse number="1">In the beginning</verse><verse number="2">And the earth</verse>
+            </chapter></book>
+            <book number="999"><chapter number="1"><verse number="1">Unknown</verse></chapter></book></testament>
+            <testament name="New"><book number="43"><chapter number="3"><verse number="16">For God</verse></chapter></book></testament>
+            </bible>"#;
+        let (rows, names) = parse("beblia", xml);
+        assert_eq!(rows, [row(1, 1, 1, "In the beginning"), row(1, 1, 2, "And the earth"), row(43, 3, 16, "For God")]);
+        assert!(names.is_empty());
+
+        let path = write("beblia-info", xml);
+        let info = detect(&path).unwrap().info(&path).unwrap();
+        assert_eq!(info.title.as_deref(), Some("King James Version"));
+        assert_eq!(info.language.as_deref(), Some("English"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn unknown_layouts_are_rejected() {
+        let path = write("unknown", "<html><body>Not a Bible</body></html>");
+        assert!(matches!(parse_verses(&path, "t"), Err(BibleError::UnsupportedFormat(_))));
+        std::fs::write(&path, "").unwrap();
+        assert!(matches!(detect(&path), Err(BibleError::UnsupportedFormat(_))));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn zefania_names_books_and_leaves_out_notes() {
+        let xml = r#"<?xml version="1.0"?>
+            <XMLBIBLE><INFORMATION><title>Tamil Bible</title><language>TAM</language></INFORMATION>
+            <BIBLEBOOK bnumber="1" bname="ஆதியாகமம்"><CHAPTER cnumber="1">
+              <CAPTION>The creation</CAPTION>
+              <VERS vnumber="1">ஆதியிலே<NOTE>a note</NOTE> தேவன்</VERS>
+              <VERS vnumber="2">And<XREF fscope="Ps 33:6">Ps 33:6</XREF> the earth</VERS>
+            </CHAPTER></BIBLEBOOK>
+            <BIBLEBOOK bnumber="999" bname="Nothing"><CHAPTER cnumber="1"><VERS vnumber="1">x</VERS></CHAPTER></BIBLEBOOK>
+            </XMLBIBLE>"#;
+        let (rows, names) = parse("zefania", xml);
+        assert_eq!(rows, [row(1, 1, 1, "ஆதியிலே தேவன்"), row(1, 1, 2, "And the earth")]);
+        assert_eq!(names, HashMap::from([(1, "ஆதியாகமம்".to_string())]));
+
+        let path = write("zefania-info", xml);
+        let info = detect(&path).unwrap().info(&path).unwrap();
+        assert_eq!((info.title.as_deref(), info.language.as_deref()), (Some("Tamil Bible"), Some("TAM")));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn opensong_matches_names_then_follows_the_book_order() {
+        // A New Testament with local names: only the first is recognised
+        let (rows, names) = parse(
+            "opensong",
+            r#"<bible><b n="Matthew"><c n="1"><v n="1">The book</v></c></b>
+            <b n="Marko"><c n="1"><v n="1">The beginning</v></c></b>
+            <b n="Luka"><c n="1"><v n="1">Forasmuch</v></c></b>
+            <b n="Rom"><c n="8"><v n="28">And we know</v></c></b></bible>"#,
+        );
+        assert_eq!(
+            rows,
+            [row(40, 1, 1, "The book"), row(41, 1, 1, "The beginning"), row(42, 1, 1, "Forasmuch"), row(45, 8, 28, "And we know")]
+        );
+        assert_eq!(names.get(&41).map(String::as_str), Some("Marko"));
+    }
+
+    #[test]
+    fn zefania_books_past_66_are_found_by_name() {
+        let (rows, names) = parse(
+            "zefania-deuterocanon",
+            r#"<XMLBIBLE><BIBLEBOOK bnumber="69" bname="Tobit"><CHAPTER cnumber="1"><VERS vnumber="1">Tobit</VERS></CHAPTER></BIBLEBOOK>
+            <BIBLEBOOK bnumber="74" bsname="Sir" bname="Jesus Sirach"><CHAPTER cnumber="1"><VERS vnumber="1">All wisdom</VERS></CHAPTER></BIBLEBOOK>
+            <BIBLEBOOK bnumber="80" bname="Psalm"><CHAPTER cnumber="1"><VERS vnumber="1">Not a Psalm</VERS></CHAPTER></BIBLEBOOK>
+            <BIBLEBOOK bnumber="81" bname="Unbekannt"><CHAPTER cnumber="1"><VERS vnumber="1">x</VERS></CHAPTER></BIBLEBOOK>
+            </XMLBIBLE>"#,
+        );
+        let id = |code| catalogue::id_for_usfm(code).unwrap();
+        assert_eq!(rows, [row(id("TOB"), 1, 1, "Tobit"), row(id("SIR"), 1, 1, "All wisdom")]);
+        assert_eq!(names.get(&id("SIR")).map(String::as_str), Some("Jesus Sirach"));
+    }
+}
@@ -0,0 +1,87 @@
+//! On-disk copies of remote translation indexes under `index_cache/`. A copy is revalidated with
+//! If-None-Match / If-Modified-Since, so an unchanged index costs one small request, and it is
+//! used as it stands when the network is slow or unavailable.
+
+use super::app_data_dir;
+use crate::error::BibleError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A copy younger than this is used without asking the server
+const MAX_AGE: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Unix seconds of the last time the server confirmed or sent this copy
+    fetched_at: u64,
+}
+
+/// A cached index and the validators it was served with
+#[derive(Debug, Clone)]
+pub struct CachedIndex {
+    pub bytes: Vec<u8>,
+    meta: CacheMeta,
+}
+
+impl CachedIndex {
+    pub fn is_fresh(&self) -> bool {
+        now().saturating_sub(self.meta.fetched_at) < MAX_AGE.as_secs()
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        self.meta.etag.as_deref()
+    }
+
+    pub fn last_modified(&self) -> Option<&str> {
+        self.meta.last_modified.as_deref()
+    }
+}
+
+/// The cached copy of `url`, if there is a readable one
+pub fn read(url: &str) -> Option<CachedIndex> {
+    let (body_path, meta_path) = paths(url).ok()?;
+    let meta: CacheMeta = serde_json::from_slice(&std::fs::read(meta_path).ok()?).ok()?;
+    if meta.url != url {
+        return None;
+    }
+    let bytes = std::fs::read(body_path).ok()?;
+    Some(CachedIndex { bytes, meta })
+}
+
+/// Store a freshly downloaded index with its validators
+pub fn write(url: &str, bytes: &[u8], etag: Option<String>, last_modified: Option<String>) -> Result<(), BibleError> {
+    let (body_path, _) = paths(url)?;
+    if let Some(dir) = body_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| BibleError::io("create", dir, e))?;
+    }
+    std::fs::write(&body_path, bytes).map_err(|e| BibleError::io("write", &body_path, e))?;
+    write_meta(&CacheMeta { url: url.to_string(), etag, last_modified, fetched_at: now() })
+}
+
+/// The server answered 304: the copy is current as of now
+pub fn touch(cached: &CachedIndex) -> Result<(), BibleError> {
+    write_meta(&CacheMeta { fetched_at: now(), ..cached.meta.clone() })
+}
+
+fn write_meta(meta: &CacheMeta) -> Result<(), BibleError> {
+    let (_, meta_path) = paths(&meta.url)?;
+    let bytes = serde_json::to_vec_pretty(meta).map_err(|e| BibleError::serialize(&meta_path, e))?;
+    std::fs::write(&meta_path, bytes).map_err(|e| BibleError::io("write", &meta_path, e))
+}
+
+/// Body and metadata files for a URL, named after a hash of it
+fn paths(url: &str) -> Result<(PathBuf, PathBuf), BibleError> {
+    let digest = Sha256::digest(url.as_bytes());
+    let key: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    let dir = app_data_dir()?.join("index_cache");
+    Ok((dir.join(format!("{}.json", key)), dir.join(format!("{}.meta.json", key))))
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
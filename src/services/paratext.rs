@@ -0,0 +1,400 @@
+//! Importers for Paratext exports: USFM (`\id`, `\c`, `\v`, …) and USX.
+//! Both arrive as one file per book and are kept that way in a directory under `translations/`.
+//! A verse bridge (`\v 3-4`, `number="3-4"`) is stored as its first verse only, holding the
+//! whole bridge's text; the later verses of the bridge are not listed on their own.
+
+use super::catalogue;
+use super::formats::ParsedText;
+use crate::error::BibleError;
+use crate::types::Verse;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// USFM markers whose content is not verse text (titles, headings, introductions, remarks)
+const NON_TEXT_MARKERS: &[&str] = &[
+    "id", "ide", "h", "toc1", "toc2", "toc3", "toca1", "toca2", "toca3", "mt", "mt1", "mt2", "mt3", "mt4",
+    "mte", "mte1", "mte2", "ms", "ms1", "ms2", "ms3", "mr", "s", "s1", "s2", "s3", "s4", "sr", "r", "d",
+    "sp", "rem", "sts", "cl", "cp", "imt", "imt1", "imt2", "is", "is1", "is2", "ip", "ipi",
+    "im", "io", "io1", "io2", "iot", "ior", "ie", "usfm",
+];
+
+/// Spans skipped up to their closing marker (`\f*`, `\va*`, …): footnotes, cross references,
+/// alternate and published chapter/verse numbers, figures and quotation references
+const SKIPPED_SPANS: &[&str] = &["f", "fe", "x", "ef", "ex", "ca", "va", "vp", "fig", "rq"];
+
+/// Book files of an imported translation directory, in a stable order
+pub fn book_files(dir: &Path) -> Result<Vec<PathBuf>, BibleError> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| BibleError::io("read", dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Parse every USFM book file in a directory
+pub fn parse_usfm_dir(dir: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+    let mut text = ParsedText::default();
+    for file in book_files(dir)? {
+        text.extend(parse_usfm_file(&file, translation_id)?);
+    }
+    Ok(in_print_order(text))
+}
+
+/// Parse every USX book file in a directory
+pub fn parse_usx_dir(dir: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+    let mut text = ParsedText::default();
+    for file in book_files(dir)? {
+        text.extend(parse_usx_file(&file, translation_id)?);
+    }
+    Ok(in_print_order(text))
+}
+
+/// File names say nothing reliable about book order, so books from separate files are put in
+/// print order; verses keep their order within each book
+fn in_print_order(mut text: ParsedText) -> ParsedText {
+    let position: HashMap<u32, u32> = catalogue::books().iter().map(|b| (b.id, b.order_index)).collect();
+    text.verses.sort_by_key(|v| position.get(&v.book_id).copied().unwrap_or(u32::MAX));
+    text
+}
+
+pub fn parse_usfm_file(path: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+    let bytes = std::fs::read(path).map_err(|e| BibleError::io("read", path, e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(parse_usfm(text.trim_start_matches('\u{feff}'), translation_id))
+}
+
+/// Parse USFM text. A file may hold several books, each introduced by `\id` and named by `\h`.
+pub fn parse_usfm(text: &str, translation_id: &str) -> ParsedText {
+    let mut out = VerseBuilder::new(translation_id);
+    let mut book: Option<u32> = None;
+    let mut chapter: Option<u32> = None;
+    // Closing marker of the footnote/cross-reference being skipped, e.g. "f*"
+    let mut skip_until: Option<String> = None;
+
+    // Text before the first marker is never verse text
+    for segment in text.split('\\').skip(1) {
+        let marker_end = segment.find(|c: char| c.is_whitespace() || c == '*').map(|i| {
+            if segment[i..].starts_with('*') { i + 1 } else { i }
+        }).unwrap_or(segment.len());
+        let marker = &segment[..marker_end];
+        // One whitespace character after an opening marker belongs to the marker; after a closing one it's text
+        let rest = &segment[marker_end..];
+        let content = if marker.ends_with('*') { rest } else { rest.strip_prefix(char::is_whitespace).unwrap_or(rest) };
+
+        if let Some(end) = &skip_until {
+            if marker == end {
+                skip_until = None;
+                out.push_text(content);
+            }
+            continue;
+        }
+
+        // Strip the "+" nesting prefix (\+wj) before looking the marker up
+        let name = marker.trim_start_matches('+');
+        match name {
+            "id" => {
+                out.finish();
+                book = content.split_whitespace().next().and_then(catalogue::id_for_usfm);
+                chapter = None;
+            }
+            "c" => {
+                out.finish();
+                chapter = leading_number(content);
+            }
+            "v" => {
+                out.finish();
+                let mut parts = content.splitn(2, char::is_whitespace);
+                let number = parts.next().and_then(leading_number);
+                if let (Some(bid), Some(ch), Some(vs)) = (book, chapter, number) {
+                    out.start(bid, ch, vs);
+                    out.push_text(parts.next().unwrap_or(""));
+                }
+            }
+            "h" => {
+                out.pause();
+                if let Some(bid) = book {
+                    out.name_book(bid, content);
+                }
+            }
+            n if SKIPPED_SPANS.contains(&n) => skip_until = Some(format!("{}*", n)),
+            n if NON_TEXT_MARKERS.contains(&n) => {
+                // A heading ends the verse text that precedes it
+                out.pause();
+            }
+            n if n.ends_with('*') => {
+                // Closing character marker: whatever follows continues the verse
+                out.push_text(content);
+            }
+            _ => {
+                // Paragraph or character marker; \w word|attrs keeps only the word
+                out.resume();
+                out.push_text(content.split('|').next().unwrap_or(""));
+            }
+        }
+    }
+
+    out.into_text()
+}
+
+/// Parse one USX book file (USX 2 plain milestones or USX 3 `sid`/`eid` milestones)
+pub fn parse_usx_file(path: &Path, translation_id: &str) -> Result<ParsedText, BibleError> {
+    let mut reader = Reader::from_file(path).map_err(|e| BibleError::io("open", path, e))?;
+    let mut buf = Vec::new();
+    let mut out = VerseBuilder::new(translation_id);
+    let mut book: Option<u32> = None;
+    let mut chapter: Option<u32> = None;
+    // Depth of elements whose text isn't verse text (notes, headings, book id line)
+    let mut skip_depth = 0u32;
+    // Text of the running header (`<para style="h">`), the book's name in this translation
+    let mut header: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(e)) => {
+                let style = attr(&e, b"style").unwrap_or_default();
+                match e.local_name().as_ref() {
+                    b"book" => {
+                        book = attr(&e, b"code").as_deref().and_then(catalogue::id_for_usfm);
+                        skip_depth += 1;
+                    }
+                    b"note" | b"figure" => skip_depth += 1,
+                    b"char" if SKIPPED_SPANS.contains(&style.as_str()) => skip_depth += 1,
+                    b"para" if style == "h" && skip_depth == 0 => {
+                        header = Some(String::new());
+                        skip_depth += 1;
+                    }
+                    b"para" if NON_TEXT_MARKERS.contains(&style.as_str()) => skip_depth += 1,
+                    _ if skip_depth > 0 => skip_depth += 1,
+                    b"para" => out.push_text(" "),
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"book" => book = attr(&e, b"code").as_deref().and_then(catalogue::id_for_usfm),
+                b"chapter" => {
+                    out.finish();
+                    if attr(&e, b"eid").is_none() {
+                        chapter = attr(&e, b"number").as_deref().and_then(leading_number);
+                    }
+                }
+                b"verse" => {
+                    out.finish();
+                    if attr(&e, b"eid").is_none() {
+                        let number = attr(&e, b"number").as_deref().and_then(leading_number);
+                        if let (Some(bid), Some(ch), Some(vs)) = (book, chapter, number) {
+                            out.start(bid, ch, vs);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::End(e)) => {
+                if skip_depth > 0 {
+                    skip_depth -= 1;
+                    if skip_depth == 0 {
+                        if let (Some(bid), Some(name)) = (book, header.take()) {
+                            out.name_book(bid, &name);
+                        }
+                    }
+                } else if e.local_name().as_ref() == b"para" {
+                    out.push_text(" ");
+                }
+            }
+            Ok(Event::Text(e)) if skip_depth == 0 => out.push_text(&e.decode().unwrap_or_default()),
+            Ok(Event::Text(e)) => {
+                if let Some(name) = header.as_mut() {
+                    name.push_str(&e.decode().unwrap_or_default());
+                }
+            }
+            Ok(Event::GeneralRef(e)) if skip_depth == 0 => {
+                if let Ok(Some(ch)) = e.resolve_char_ref() {
+                    out.push_text(&ch.to_string());
+                } else if let Some(s) = e.decode().ok().and_then(|n| quick_xml::escape::resolve_predefined_entity(&n)) {
+                    out.push_text(s);
+                }
+            }
+            Err(e) => return Err(BibleError::parse_at(path, reader.buffer_position(), e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(out.into_text())
+}
+
+fn attr(e: &quick_xml::events::BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .with_checks(false)
+        .flatten()
+        .find(|a| a.key.as_ref() == key)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()))
+}
+
+/// "16", "16a" and "16-18" all start verse/chapter 16; the rest of a bridge is dropped
+fn leading_number(s: &str) -> Option<u32> {
+    let digits: String = s.trim().chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Collects verse text between markers; paused while inside headings
+struct VerseBuilder<'a> {
+    translation_id: &'a str,
+    current: Option<(u32, u32, u32)>,
+    paused: bool,
+    text: String,
+    verses: Vec<Verse>,
+    book_names: HashMap<u32, String>,
+}
+
+impl<'a> VerseBuilder<'a> {
+    fn new(translation_id: &'a str) -> Self {
+        Self {
+            translation_id,
+            current: None,
+            paused: false,
+            text: String::new(),
+            verses: Vec::new(),
+            book_names: HashMap::new(),
+        }
+    }
+
+    /// Record the book's name from its header; the first one wins
+    fn name_book(&mut self, book_id: u32, name: &str) {
+        let name = tidy(name);
+        if !name.is_empty() {
+            self.book_names.entry(book_id).or_insert(name);
+        }
+    }
+
+    fn start(&mut self, book_id: u32, chapter: u32, verse: u32) {
+        self.finish();
+        self.current = Some((book_id, chapter, verse));
+    }
+
+    fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.current.is_some() && !self.paused {
+            self.text.push_str(text);
+        }
+    }
+
+    fn finish(&mut self) {
+        self.paused = false;
+        if let Some((bid, ch, vs)) = self.current.take() {
+            self.verses.push(Verse {
+                id: format!("{}:{}:{}:{}", self.translation_id, bid, ch, vs),
+                translation_id: self.translation_id.to_string(),
+                book_id: bid,
+                chapter: ch,
+                verse: vs,
+                text: tidy(&self.text),
+            });
+        }
+        self.text.clear();
+    }
+
+    fn into_text(mut self) -> ParsedText {
+        self.finish();
+        ParsedText { verses: self.verses, book_names: self.book_names }
+    }
+}
+
+fn tidy(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(text: &ParsedText) -> Vec<(u32, u32, u32, &str)> {
+        text.verses.iter().map(|v| (v.book_id, v.chapter, v.verse, v.text.as_str())).collect()
+    }
+
+    #[test]
+    fn usfm_verses_skip_headings_notes_and_attributes() {
+        let text = parse_usfm(
+            "\\id GEN Genesis\n\\h Genesis\n\\mt1 Genesis\n\\c 1\n\\s1 The Creation\n\\p\n\
+             \\v 1 In the beginning \\nd God\\nd* created\\f + \\fr 1:1 \\ft note\\f* the heaven.\n\
+             \\v 2 And the \\w earth|strong=\"H776\"\\w* was.\n\\s1 Light\n\\p\n\\v 3-4 Light.\n",
+            "t",
+        );
+        assert_eq!(
+            texts(&text),
+            vec![
+                (1, 1, 1, "In the beginning God created the heaven."),
+                (1, 1, 2, "And the earth was."),
+                (1, 1, 3, "Light."),
+            ]
+        );
+        assert_eq!(text.book_names.get(&1).map(String::as_str), Some("Genesis"));
+    }
+
+    #[test]
+    fn usfm_alternate_numbers_figures_and_quote_references_are_not_verse_text() {
+        let text = parse_usfm(
+            "\\id PSA\n\\c 51 \\ca 50\\ca*\n\\p\n\\v 1 \\va 3\\va* Have mercy upon me, O God.\n\
+             \\v 2 Wash me \\fig Washing|src=\"a.jpg\"\\fig* throughly.\n\
+             \\v 3 For I acknowledge \\rq Isa 1:16\\rq* my transgressions.\n",
+            "t",
+        );
+        assert_eq!(
+            texts(&text),
+            vec![
+                (19, 51, 1, "Have mercy upon me, O God."),
+                (19, 51, 2, "Wash me throughly."),
+                (19, 51, 3, "For I acknowledge my transgressions."),
+            ]
+        );
+    }
+
+    #[test]
+    fn usx_milestones_headers_and_skipped_spans() {
+        let path = super::super::testing::scratch_path("milestones.usx");
+        std::fs::write(
+            &path,
+            r#"<usx version="3.0"><book code="JHN" style="id">John</book><para style="h">யோவான்</para>
+<chapter number="3" style="c" sid="JHN 3"/><para style="s1">Nicodemus</para>
+<para style="p"><verse number="16" style="v" sid="JHN 3:16"/><char style="va">15</char>For God so <char style="wj">loved</char> the world<note caller="+" style="f">note</note>.<verse eid="JHN 3:16"/>
+<verse number="17" style="v" sid="JHN 3:17"/>For God sent</para><para style="q1">not his Son<verse eid="JHN 3:17"/></para><chapter eid="JHN 3"/></usx>"#,
+        )
+        .unwrap();
+        let text = parse_usx_file(&path, "t").unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            texts(&text),
+            vec![(43, 3, 16, "For God so loved the world."), (43, 3, 17, "For God sent not his Son")]
+        );
+        assert_eq!(text.book_names.get(&43).map(String::as_str), Some("யோவான்"));
+    }
+
+    #[test]
+    fn verse_bridges_are_kept_under_their_first_verse() {
+        let usfm = parse_usfm("\\id ROM\n\\c 1\n\\p\n\\v 1-2 Paul, a servant.\n\\v 3 Concerning his Son\n", "t");
+        assert_eq!(texts(&usfm), vec![(45, 1, 1, "Paul, a servant."), (45, 1, 3, "Concerning his Son")]);
+
+        let path = super::super::testing::scratch_path("bridge.usx");
+        std::fs::write(
+            &path,
+            r#"<usx version="3.0"><book code="ROM" style="id"/><chapter number="1" style="c" sid="ROM 1"/>
+<para style="p"><verse number="1-2" style="v" sid="ROM 1:1-2"/>Paul, a servant.<verse eid="ROM 1:1-2"/>
+<verse number="3" style="v" sid="ROM 1:3"/>Concerning his Son<verse eid="ROM 1:3"/></para></usx>"#,
+        )
+        .unwrap();
+        let usx = parse_usx_file(&path, "t").unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(texts(&usx), texts(&usfm));
+    }
+}
@@ -0,0 +1,124 @@
+//! Background download queue: takes any number of translation ids and fetches a few at a time,
+//! publishing each item's state so the UI can follow along without holding the service.
+
+use super::download::{CancelToken, DownloadProgress};
+use super::SharedBibleService;
+use crate::error::BibleError;
+use crate::types::TranslationStatus;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{watch, Semaphore};
+
+/// Downloads running at once; the rest wait their turn
+pub const DEFAULT_CONCURRENT_DOWNLOADS: usize = 3;
+
+/// Where one queued translation stands
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueItemState {
+    Queued,
+    Downloading(Option<DownloadProgress>),
+    Done,
+    Failed(BibleError),
+}
+
+/// Every translation the queue has seen this session, by id. Cancelled items are dropped.
+pub type QueueSnapshot = HashMap<String, QueueItemState>;
+
+/// Cheap to clone; clones share the same queue
+#[derive(Clone)]
+pub struct DownloadQueue {
+    service: SharedBibleService,
+    permits: Arc<Semaphore>,
+    state: Arc<watch::Sender<QueueSnapshot>>,
+    tokens: Arc<Mutex<HashMap<String, CancelToken>>>,
+}
+
+impl DownloadQueue {
+    pub fn new(service: SharedBibleService, max_concurrent: usize) -> Self {
+        Self {
+            service,
+            permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            state: Arc::new(watch::Sender::new(QueueSnapshot::new())),
+            tokens: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Queue translations for download. Ids already queued or downloading are left alone;
+    /// translations that are on disk and up to date finish straight away.
+    pub fn enqueue<I>(&self, translation_ids: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        for id in translation_ids {
+            let token = {
+                let mut tokens = self.tokens.lock().unwrap();
+                if tokens.contains_key(&id) {
+                    continue;
+                }
+                let token = CancelToken::new();
+                tokens.insert(id.clone(), token.clone());
+                token
+            };
+            self.set(&id, QueueItemState::Queued);
+            let queue = self.clone();
+            tokio::spawn(async move { queue.run_item(id, token).await });
+        }
+    }
+
+    /// Stop one translation, whether it is still waiting or already downloading
+    pub fn cancel(&self, translation_id: &str) {
+        if let Some(token) = self.tokens.lock().unwrap().get(translation_id) {
+            token.cancel();
+        }
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<QueueSnapshot> {
+        self.state.subscribe()
+    }
+
+    async fn run_item(&self, id: String, token: CancelToken) {
+        let result = self.download(&id, &token).await;
+        self.tokens.lock().unwrap().remove(&id);
+        match result {
+            Ok(()) => self.set(&id, QueueItemState::Done),
+            Err(BibleError::Cancelled) => {
+                self.state.send_modify(|s| {
+                    s.remove(&id);
+                });
+            }
+            Err(e) => self.set(&id, QueueItemState::Failed(e)),
+        }
+    }
+
+    async fn download(&self, id: &str, token: &CancelToken) -> Result<(), BibleError> {
+        // Waiting for a turn can be cancelled here; once running, the job handles it and cleans up
+        let _permit = tokio::select! {
+            permit = self.permits.acquire() => permit.map_err(|_| BibleError::Cancelled)?,
+            _ = token.cancelled() => return Err(BibleError::Cancelled),
+        };
+        self.set(id, QueueItemState::Downloading(None));
+
+        // Only hold the service to plan and to take in the result; transfer and import run unlocked
+        let job = {
+            let mut svc = self.service.clone().lock_owned().await;
+            match svc.translation_status(id) {
+                TranslationStatus::Downloaded if svc.has_current_store(id) && !svc.update_available(id) => return Ok(()),
+                // Nothing listed to fetch it from: sideloaded texts and core entries stay as they are
+                TranslationStatus::Downloaded | TranslationStatus::Core if svc.remote_file(id).is_none() => return Ok(()),
+                // A damaged file is thrown away so the download starts clean
+                TranslationStatus::Error => svc.remove_translation_files(id).await?,
+                _ => {}
+            }
+            svc.prepare_download(id).await?
+        };
+        job.run(token, |p| self.set(id, QueueItemState::Downloading(Some(p)))).await?;
+        let imported = job.import().await;
+        self.service.clone().lock_owned().await.finish_download(&job, imported)
+    }
+
+    fn set(&self, id: &str, item: QueueItemState) {
+        self.state.send_modify(|s| {
+            s.insert(id.to_string(), item);
+        });
+    }
+}
@@ -0,0 +1,579 @@
+//! Reading passage references such as "Jn 3:16-18", "1 Cor 13", "Rom 8:28; 12:1-2" or
+//! "Ps 23:1–24:3" into verse ranges, and writing ranges back out. Books are recognised by English
+//! name or abbreviation, OSIS code or Tamil name, and any unambiguous start of a name.
+
+use super::catalogue;
+use crate::data;
+use crate::error::BibleError;
+use crate::types::{BibleReference, Book, VerseRange};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Range separators: hyphen, en and em dash, and the Unicode hyphen
+const DASHES: [char; 4] = ['-', '–', '—', '‐'];
+
+/// Chapter/verse separators; OSIS uses the dot
+const CHAPTER_VERSE: [char; 2] = [':', '.'];
+
+/// Common abbreviations beyond the standard names and OSIS codes, in normalised form
+const ALIASES: &[(&str, u32)] = &[
+    ("gn", 1), ("ge", 1),
+    ("ex", 2), ("exo", 2),
+    ("lv", 3), ("le", 3),
+    ("nm", 4), ("nb", 4), ("nu", 4),
+    ("dt", 5), ("deu", 5),
+    ("jos", 6), ("jsh", 6),
+    ("jdg", 7), ("jg", 7), ("jdgs", 7),
+    ("ru", 8), ("rth", 8),
+    ("1sa", 9), ("1sm", 9), ("2sa", 10), ("2sm", 10),
+    ("1ki", 11), ("1kg", 11), ("2ki", 12), ("2kg", 12),
+    ("1ch", 13), ("2ch", 14),
+    ("ezr", 15),
+    ("ne", 16),
+    ("es", 17), ("est", 17),
+    ("jb", 18),
+    ("psa", 19), ("psm", 19), ("pss", 19), ("psalm", 19),
+    ("pr", 20), ("prv", 20),
+    ("ec", 21), ("ecc", 21), ("qoh", 21),
+    ("sos", 22), ("ss", 22), ("songofsongs", 22), ("canticles", 22),
+    ("is", 23),
+    ("je", 24),
+    ("la", 25),
+    ("eze", 26), ("ezk", 26),
+    ("da", 27), ("dn", 27),
+    ("ho", 28),
+    ("jl", 29),
+    ("am", 30),
+    ("ob", 31),
+    ("jnh", 32),
+    ("mi", 33),
+    ("na", 34),
+    ("hb", 35),
+    ("zp", 36),
+    ("hg", 37),
+    ("zc", 38),
+    ("ml", 39),
+    ("mt", 40),
+    ("mk", 41), ("mr", 41), ("mrk", 41),
+    ("lk", 42), ("lu", 42),
+    ("jn", 43), ("jhn", 43),
+    ("ac", 44),
+    ("ro", 45), ("rm", 45),
+    ("1co", 46), ("2co", 47),
+    ("ga", 48),
+    ("ep", 49),
+    ("php", 50), ("pp", 50),
+    ("1th", 52), ("2th", 53),
+    ("1ti", 54), ("2ti", 55),
+    ("ti", 56),
+    ("phm", 57), ("pm", 57),
+    ("he", 58),
+    ("jm", 59),
+    ("1pe", 60), ("1pt", 60), ("2pe", 61), ("2pt", 61),
+    ("1jn", 62), ("1jo", 62), ("1jhn", 62),
+    ("2jn", 63), ("2jo", 63), ("2jhn", 63),
+    ("3jn", 64), ("3jo", 64), ("3jhn", 64),
+    ("jud", 65), ("jde", 65),
+    ("re", 66), ("rv", 66), ("revelations", 66),
+    ("ecclus", 70), ("ecclesiasticus", 70),
+    ("1mac", 72), ("2mac", 73), ("3mac", 77), ("4mac", 78),
+];
+
+struct Catalogue {
+    books: Vec<Book>,
+    /// Normalised names, OSIS codes and aliases, each naming exactly one book
+    keys: HashMap<String, u32>,
+    /// Normalised English and Tamil names, for matching on the start of a name
+    names: Vec<(String, u32)>,
+}
+
+fn catalogue() -> &'static Catalogue {
+    static CATALOGUE: OnceLock<Catalogue> = OnceLock::new();
+    CATALOGUE.get_or_init(|| {
+        let books = catalogue::books();
+        let tamil = data::localized_book_names("ta");
+        let mut names = Vec::new();
+        for book in &books {
+            names.push((normalize(&book.name), book.id));
+            if let Some(name) = tamil.and_then(|t| t.get(&book.id)) {
+                names.push((normalize(name), book.id));
+            }
+        }
+        let mut keys: HashMap<String, u32> = ALIASES.iter().map(|(alias, id)| (alias.to_string(), *id)).collect();
+        keys.extend(books.iter().map(|b| (normalize(&b.abbreviation), b.id)));
+        keys.extend(names.iter().cloned());
+        Catalogue { books, keys, names }
+    })
+}
+
+/// Lowercased with spaces and dots dropped, and a leading roman numeral ("II Kings") as a digit
+fn normalize(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let mut words = lower.split(|c: char| c.is_whitespace() || c == '.').filter(|w| !w.is_empty());
+    let mut key = String::new();
+    if let Some(first) = words.next() {
+        let rest: String = words.collect();
+        let first = match first {
+            "i" if !rest.is_empty() => "1",
+            "ii" if !rest.is_empty() => "2",
+            "iii" if !rest.is_empty() => "3",
+            word => word,
+        };
+        key.push_str(first);
+        key.push_str(&rest);
+    }
+    key
+}
+
+/// The book a name, abbreviation or the unambiguous start of a name refers to
+pub fn find_book(name: &str) -> Option<&'static Book> {
+    let catalogue = catalogue();
+    let key = normalize(name);
+    let id = match catalogue.keys.get(&key) {
+        Some(id) => *id,
+        None if key.chars().count() >= 2 => {
+            let mut matches = catalogue.names.iter().filter(|(n, _)| n.starts_with(&key)).map(|(_, id)| *id);
+            let first = matches.next()?;
+            if !matches.all(|id| id == first) {
+                return None;
+            }
+            first
+        }
+        None => return None,
+    };
+    catalogue.books.iter().find(|b| b.id == id)
+}
+
+/// Parse one or more references separated by `;` (each may name a book, or continue with the
+/// previous one) or `,` (continuing the same chapter when the previous part gave verses).
+/// A number alone is a chapter, except in single-chapter books, where it is a verse; a book
+/// alone is the whole book. A range may run into another book, as in "Gen 50:26 - Exod 1:5".
+pub fn parse_references(input: &str) -> Result<Vec<VerseRange>, BibleError> {
+    let mut ranges = Vec::new();
+    let mut book: Option<&Book> = None;
+    for segment in input.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        let (head, tail) = split_cross_book(segment);
+        let (name, passage) = split_book(head);
+        if !name.is_empty() {
+            book = Some(parse_book(name)?);
+        }
+        let start_book = book.ok_or_else(|| invalid(format!("\"{}\" does not name a book", segment)))?;
+        parse_passage(start_book, passage, None, &mut ranges)?;
+        let Some(tail) = tail else { continue };
+
+        // The last range parsed is where the cross-book range starts
+        let start = match ranges.pop() {
+            Some(range) if range.start == range.end || passage.trim().is_empty() => range.start,
+            _ => return Err(invalid(format!("\"{}\" is not a range", segment))),
+        };
+        let (name, passage) = split_book(tail);
+        let end_book = parse_book(name)?;
+        let (first, rest) = passage.split_once(',').map_or((passage, None), |(first, rest)| (first, Some(rest)));
+        let end = match first.trim() {
+            "" => reference(end_book, end_book.chapter_count, None),
+            first => parse_point(end_book, first, None)?,
+        };
+        check_order(&start, &end, segment)?;
+        let verse_chapter = end.verse.map(|_| end.chapter);
+        ranges.push(VerseRange { start, end });
+        if let Some(rest) = rest {
+            parse_passage(end_book, rest, verse_chapter, &mut ranges)?;
+        }
+        book = Some(end_book);
+    }
+    if ranges.is_empty() {
+        return Err(invalid(format!("\"{}\" is not a reference", input.trim())));
+    }
+    Ok(ranges)
+}
+
+fn parse_book(name: &str) -> Result<&'static Book, BibleError> {
+    find_book(name).ok_or_else(|| invalid(format!("Unknown book \"{}\"", name)))
+}
+
+/// Split "Gen 50:26 - Exod 1:5" at the dash whose far side names a book
+fn split_cross_book(segment: &str) -> (&str, Option<&str>) {
+    segment
+        .match_indices(DASHES)
+        .map(|(i, dash)| (&segment[..i], &segment[i + dash.len()..]))
+        .find(|(_, tail)| !split_book(tail.trim()).0.is_empty())
+        .map_or((segment, None), |(head, tail)| (head, Some(tail.trim())))
+}
+
+/// Split "1 Cor 13:4-7" into the book name and the chapter/verse part after it
+fn split_book(segment: &str) -> (&str, &str) {
+    let at = segment
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_digit() || c.is_whitespace() || *c == ',' || DASHES.contains(c) || CHAPTER_VERSE.contains(c))
+        .last()
+        .map_or(segment.len(), |(i, _)| i);
+    let (name, passage) = segment.split_at(at);
+    (name.trim().trim_end_matches('.'), passage.trim_start_matches(|c: char| c == '.' || c.is_whitespace()))
+}
+
+/// `verse_chapter` is the chapter a bare number at the start is a verse of, if any
+fn parse_passage(book: &Book, passage: &str, mut verse_chapter: Option<u32>, ranges: &mut Vec<VerseRange>) -> Result<(), BibleError> {
+    if passage.trim().is_empty() {
+        ranges.push(VerseRange { start: reference(book, 1, None), end: reference(book, book.chapter_count, None) });
+        return Ok(());
+    }
+    for part in passage.split(',').map(str::trim) {
+        let (from, to) = match part.split_once(DASHES) {
+            Some((from, to)) => (from.trim(), Some(to.trim())),
+            None => (part, None),
+        };
+        let start = parse_point(book, from, verse_chapter)?;
+        let end = match to {
+            Some(to) => parse_point(book, to, start.verse.map(|_| start.chapter))?,
+            None => start.clone(),
+        };
+        check_order(&start, &end, part)?;
+        // Chapter of the last verse given, which later bare numbers are verses of
+        verse_chapter = end.verse.map(|_| end.chapter);
+        ranges.push(VerseRange { start, end });
+    }
+    Ok(())
+}
+
+/// "3:16", "3.16", or a number that is a chapter or, given `verse_chapter`, a verse of it
+fn parse_point(book: &Book, text: &str, verse_chapter: Option<u32>) -> Result<BibleReference, BibleError> {
+    let number = |s: &str| s.trim().parse::<u32>().ok().filter(|n| *n > 0).ok_or_else(|| invalid(format!("\"{}\" is not a chapter or verse", text)));
+    let (chapter, verse) = match text.split_once(CHAPTER_VERSE) {
+        Some((chapter, verse)) => (number(chapter)?, Some(number(verse)?)),
+        None if book.chapter_count == 1 => (1, Some(number(text)?)),
+        None => match verse_chapter {
+            Some(chapter) => (chapter, Some(number(text)?)),
+            None => (number(text)?, None),
+        },
+    };
+    if chapter > book.chapter_count {
+        return Err(invalid(format!("{} has {} chapters", book.name, book.chapter_count)));
+    }
+    Ok(reference(book, chapter, verse))
+}
+
+fn check_order(start: &BibleReference, end: &BibleReference, text: &str) -> Result<(), BibleError> {
+    let first = (start.book_id, start.chapter, start.verse.unwrap_or(0));
+    let last = (end.book_id, end.chapter, end.verse.unwrap_or(u32::MAX));
+    if first > last {
+        return Err(invalid(format!("\"{}\" ends before it starts", text)));
+    }
+    Ok(())
+}
+
+/// Parse an OSIS reference list such as "John.3.16-John.3.18 Rom.8": space-separated, each a
+/// single point or a `-` range, where a point is a book code, `Book.chapter` or
+/// `Book.chapter.verse`. A range may run across books.
+pub fn parse_osis(osis: &str) -> Result<Vec<VerseRange>, BibleError> {
+    let ranges = osis
+        .split_whitespace()
+        .map(|text| {
+            let (from, to) = text.split_once('-').map_or((text, None), |(from, to)| (from, Some(to)));
+            let (book, chapter, verse) = osis_point(from)?;
+            let start = reference(book, chapter.unwrap_or(1), verse);
+            let end = match to {
+                Some(to) => {
+                    let (book, chapter, verse) = osis_point(to)?;
+                    reference(book, chapter.unwrap_or(book.chapter_count), verse)
+                }
+                None => reference(book, chapter.unwrap_or(book.chapter_count), verse),
+            };
+            check_order(&start, &end, text)?;
+            Ok(VerseRange { start, end })
+        })
+        .collect::<Result<Vec<_>, BibleError>>()?;
+    if ranges.is_empty() {
+        return Err(invalid("Empty OSIS reference".to_string()));
+    }
+    Ok(ranges)
+}
+
+/// One OSIS point, with its book looked up and its chapter checked against the book
+fn osis_point(text: &str) -> Result<(&'static Book, Option<u32>, Option<u32>), BibleError> {
+    let not_osis = || invalid(format!("\"{}\" is not an OSIS reference", text));
+    let (code, chapter, verse) = osis_parts(text).ok_or_else(not_osis)?;
+    let book = find_book(code).ok_or_else(|| invalid(format!("Unknown book \"{}\"", code)))?;
+    if chapter.is_some_and(|c| c > book.chapter_count) {
+        return Err(invalid(format!("{} has {} chapters", book.name, book.chapter_count)));
+    }
+    Ok((book, chapter, verse))
+}
+
+/// Split `Book`, `Book.chapter` or `Book.chapter.verse`, dropping any `work:` prefix and `!grain`
+/// suffix. A verse needs a chapter.
+pub fn osis_parts(text: &str) -> Option<(&str, Option<u32>, Option<u32>)> {
+    let text = text.rsplit_once(':').map_or(text, |(_, rest)| rest);
+    let text = text.split_once('!').map_or(text, |(point, _)| point);
+    let mut parts = text.split('.');
+    let book = parts.next().filter(|b| !b.is_empty())?;
+    let mut number = || parts.next().map(|n| n.parse::<u32>().ok().filter(|n| *n > 0));
+    let chapter = number().map_or(Some(None), |n| n.map(Some))?;
+    let verse = number().map_or(Some(None), |n| n.map(Some))?;
+    if parts.next().is_some() || (verse.is_some() && chapter.is_none()) {
+        return None;
+    }
+    Some((book, chapter, verse))
+}
+
+/// How `format_references` names books
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceStyle {
+    /// "1 Corinthians 13:4-7"
+    Long,
+    /// "1Cor 13:4-7"
+    Abbreviation,
+    /// "1Cor.13.4-1Cor.13.7", as in OSIS `osisRef` attributes
+    Osis,
+    /// Tamil book names, falling back to English for any book without one
+    Tamil,
+}
+
+impl ReferenceStyle {
+    /// How references to a translation in `language` are written by default
+    pub fn for_language(language: &str) -> Self {
+        match data::language_code(language).as_str() {
+            "ta" => ReferenceStyle::Tamil,
+            _ => ReferenceStyle::Long,
+        }
+    }
+}
+
+/// Write ranges in `style`. Apart from OSIS, consecutive ranges in one book share its name, and
+/// verses in one chapter share the chapter, as in "Romans 8:28, 31; 12:1-2". The result parses
+/// back to the same ranges with `parse_references`, or `parse_osis` for the OSIS style.
+pub fn format_references(ranges: &[VerseRange], style: ReferenceStyle) -> String {
+    if style == ReferenceStyle::Osis {
+        return ranges.iter().map(osis_range).collect::<Vec<_>>().join(" ");
+    }
+    let mut out = String::new();
+    let mut previous: Option<&BibleReference> = None;
+    for range in ranges {
+        let Some(book) = book_by_id(range.start.book_id) else {
+            continue;
+        };
+        let same_book = previous.is_some_and(|p| p.book_id == book.id);
+        let same_chapter = previous.is_some_and(|p| {
+            same_book && p.verse.is_some() && range.start.verse.is_some() && p.chapter == range.start.chapter
+        });
+        let passage = human_passage(book, range, same_chapter);
+        if previous.is_some() {
+            out.push_str(if same_chapter { ", " } else { "; " });
+        }
+        if !same_book || passage.is_empty() {
+            out.push_str(&book_name(book, style));
+            if !passage.is_empty() {
+                out.push(' ');
+            }
+        }
+        out.push_str(&passage);
+        if range.end.book_id != book.id {
+            if let Some(end_book) = book_by_id(range.end.book_id) {
+                out.push_str(" - ");
+                out.push_str(&book_name(end_book, style));
+                let end = human_point(end_book, &range.end);
+                if !end.is_empty() {
+                    out.push(' ');
+                    out.push_str(&end);
+                }
+            }
+        }
+        previous = Some(&range.end);
+    }
+    out
+}
+
+fn book_by_id(id: u32) -> Option<&'static Book> {
+    catalogue().books.iter().find(|b| b.id == id)
+}
+
+fn book_name(book: &Book, style: ReferenceStyle) -> String {
+    match style {
+        ReferenceStyle::Abbreviation | ReferenceStyle::Osis => book.abbreviation.clone(),
+        ReferenceStyle::Tamil => data::localized_book_names("ta")
+            .and_then(|names| names.get(&book.id))
+            .cloned()
+            .unwrap_or_else(|| book.name.clone()),
+        ReferenceStyle::Long => book.name.clone(),
+    }
+}
+
+/// The chapter/verse part of a range within one book ("3:16-18", "1-3"), empty for a whole
+/// book, and without the chapter when it continues the previous one
+fn human_passage(book: &Book, range: &VerseRange, same_chapter: bool) -> String {
+    let (start, end) = (&range.start, &range.end);
+    if end.book_id != book.id {
+        return human_point(book, start);
+    }
+    if start.chapter == 1 && start.verse.is_none() && end.chapter == book.chapter_count && end.verse.is_none() {
+        return String::new();
+    }
+    let mut out = match (same_chapter, start.verse) {
+        (true, Some(verse)) => verse.to_string(),
+        _ => human_point(book, start),
+    };
+    if end != start {
+        out.push('-');
+        match (start.verse, end.verse) {
+            (Some(_), Some(verse)) if end.chapter == start.chapter => out.push_str(&verse.to_string()),
+            _ => out.push_str(&human_point(book, end)),
+        }
+    }
+    out
+}
+
+/// "3:16", "3" for a whole chapter, or the verse alone in a single-chapter book, where the
+/// whole chapter is the book and so has no number of its own
+fn human_point(book: &Book, point: &BibleReference) -> String {
+    match point.verse {
+        Some(verse) if book.chapter_count == 1 => verse.to_string(),
+        Some(verse) => format!("{}:{}", point.chapter, verse),
+        None if book.chapter_count == 1 => String::new(),
+        None => point.chapter.to_string(),
+    }
+}
+
+/// "John.3.16-John.3.18", "John.3" for a whole chapter, "John" for a whole book
+fn osis_range(range: &VerseRange) -> String {
+    let (start, end) = (&range.start, &range.end);
+    let whole_book = book_by_id(start.book_id).is_some_and(|b| {
+        end.book_id == b.id && start.chapter == 1 && start.verse.is_none() && end.chapter == b.chapter_count && end.verse.is_none()
+    });
+    match book_by_id(start.book_id) {
+        Some(book) if whole_book => book.abbreviation.clone(),
+        _ if start == end => osis_ref(start),
+        _ => format!("{}-{}", osis_ref(start), osis_ref(end)),
+    }
+}
+
+fn osis_ref(point: &BibleReference) -> String {
+    let code = book_by_id(point.book_id).map_or_else(|| point.book_id.to_string(), |b| b.abbreviation.clone());
+    match point.verse {
+        Some(verse) => format!("{}.{}.{}", code, point.chapter, verse),
+        None => format!("{}.{}", code, point.chapter),
+    }
+}
+
+fn reference(book: &Book, chapter: u32, verse: Option<u32>) -> BibleReference {
+    BibleReference { book_id: book.id, chapter, verse }
+}
+
+fn invalid(message: String) -> BibleError {
+    BibleError::InvalidInput(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(book_id: u32, chapter: u32, verse: Option<u32>) -> BibleReference {
+        BibleReference { book_id, chapter, verse }
+    }
+
+    fn range(start: (u32, u32, Option<u32>), end: (u32, u32, Option<u32>)) -> VerseRange {
+        VerseRange { start: point(start.0, start.1, start.2), end: point(end.0, end.1, end.2) }
+    }
+
+    fn verses(book_id: u32, chapter: u32, from: u32, to: u32) -> VerseRange {
+        range((book_id, chapter, Some(from)), (book_id, chapter, Some(to)))
+    }
+
+    #[test]
+    fn parses_names_abbreviations_and_lists() {
+        assert_eq!(parse_references("Jn 3:16-18").unwrap(), [verses(43, 3, 16, 18)]);
+        assert_eq!(parse_references("1 Cor 13").unwrap(), [range((46, 13, None), (46, 13, None))]);
+        assert_eq!(parse_references("I Corinthians 13").unwrap(), [range((46, 13, None), (46, 13, None))]);
+        assert_eq!(parse_references("Rom 8:28; 12:1-2").unwrap(), [verses(45, 8, 28, 28), verses(45, 12, 1, 2)]);
+        assert_eq!(parse_references("Rom 8:28, 31").unwrap(), [verses(45, 8, 28, 28), verses(45, 8, 31, 31)]);
+        assert_eq!(parse_references("Ps 23:1–24:3").unwrap(), [range((19, 23, Some(1)), (19, 24, Some(3)))]);
+        assert_eq!(parse_references("John.3.16").unwrap(), [verses(43, 3, 16, 16)]);
+        assert_eq!(parse_references("ஆதியாகமம் 1:1").unwrap(), [verses(1, 1, 1, 1)]);
+    }
+
+    #[test]
+    fn single_chapter_books_and_whole_books() {
+        assert_eq!(parse_references("Jude 3").unwrap(), [verses(65, 1, 3, 3)]);
+        assert_eq!(parse_references("Obadiah").unwrap(), [range((31, 1, None), (31, 1, None))]);
+        assert_eq!(parse_references("Genesis").unwrap(), [range((1, 1, None), (1, 50, None))]);
+    }
+
+    #[test]
+    fn ranges_across_books() {
+        assert_eq!(parse_references("Genesis 1:1 - Exodus 2:3").unwrap(), [range((1, 1, Some(1)), (2, 2, Some(3)))]);
+        assert_eq!(
+            parse_references("Gen 50:26-Exod 1:5, 7; 3").unwrap(),
+            [range((1, 50, Some(26)), (2, 1, Some(5))), verses(2, 1, 7, 7), range((2, 3, None), (2, 3, None))]
+        );
+        assert_eq!(parse_references("Malachi - Matthew 2").unwrap(), [range((39, 1, None), (40, 2, None))]);
+    }
+
+    #[test]
+    fn rejects_what_is_not_a_reference() {
+        assert!(parse_references("").is_err());
+        assert!(parse_references("3:16").is_err());
+        assert!(parse_references("Hezekiah 1").is_err());
+        assert!(parse_references("Jn 22").is_err());
+        assert!(parse_references("Jn 3:18-16").is_err());
+        assert!(parse_references("Exodus 1 - Genesis 2").is_err());
+        // "J" could be many books
+        assert!(find_book("J").is_none());
+    }
+
+    #[test]
+    fn parses_osis_ranges_chapters_and_books() {
+        assert_eq!(parse_osis("John.3.16-John.3.18").unwrap(), [verses(43, 3, 16, 18)]);
+        assert_eq!(parse_osis("Bible.KJV:Rom.8").unwrap(), [range((45, 8, None), (45, 8, None))]);
+        assert_eq!(parse_osis("Jude").unwrap(), [range((65, 1, None), (65, 1, None))]);
+        assert_eq!(parse_osis("Gen.50.26-Exod.1.5 Ps.23").unwrap().len(), 2);
+        assert!(parse_osis("John.3.x").is_err());
+        assert!(parse_osis("John.30").is_err());
+    }
+
+    /// Something of every shape `format_references` writes
+    fn samples() -> Vec<VerseRange> {
+        vec![
+            verses(43, 3, 16, 18),
+            verses(45, 8, 28, 28),
+            verses(45, 8, 31, 31),
+            verses(45, 12, 1, 2),
+            range((46, 13, None), (46, 13, None)),
+            range((19, 23, Some(1)), (19, 24, Some(3))),
+            range((19, 119, None), (19, 120, None)),
+            verses(65, 1, 3, 5),
+            range((31, 1, None), (31, 1, None)),
+            range((1, 1, None), (1, 50, None)),
+            range((1, 50, Some(26)), (2, 1, Some(5))),
+            verses(2, 1, 7, 7),
+            range((39, 4, None), (40, 1, None)),
+            range((62, 5, Some(21)), (63, 1, Some(2))),
+            verses(10, 7, 12, 14),
+        ]
+    }
+
+    #[test]
+    fn long_names_round_trip() {
+        let text = format_references(&samples(), ReferenceStyle::Long);
+        assert!(text.starts_with("John 3:16-18; Romans 8:28, 31; 12:1-2; 1 Corinthians 13; Psalms 23:1-24:3"), "{}", text);
+        assert_eq!(parse_references(&text).unwrap(), samples(), "{}", text);
+    }
+
+    #[test]
+    fn abbreviations_round_trip() {
+        let text = format_references(&samples(), ReferenceStyle::Abbreviation);
+        assert_eq!(parse_references(&text).unwrap(), samples(), "{}", text);
+    }
+
+    #[test]
+    fn osis_round_trips() {
+        let text = format_references(&samples(), ReferenceStyle::Osis);
+        assert!(text.starts_with("John.3.16-John.3.18 Rom.8.28 Rom.8.31"), "{}", text);
+        assert_eq!(parse_osis(&text).unwrap(), samples(), "{}", text);
+    }
+
+    #[test]
+    fn tamil_names_round_trip() {
+        let text = format_references(&samples(), ReferenceStyle::Tamil);
+        assert!(text.starts_with("யோவான் 3:16-18"), "{}", text);
+        assert_eq!(parse_references(&text).unwrap(), samples(), "{}", text);
+    }
+}
@@ -0,0 +1,260 @@
+//! Full-text verse search: tokenising, matching and ranking
+
+use super::store::ChapterStore;
+use super::SourceStamp;
+use crate::error::BibleError;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Maximum number of verses returned in a single `SearchResult`
+pub const SEARCH_RESULT_LIMIT: usize = 200;
+
+/// Bumped whenever the on-disk index layout or tokenizer changes
+const INDEX_VERSION: u32 = 2;
+
+/// Split text into lowercase search terms.
+/// Non-ASCII letters and combining marks (e.g. Tamil vowel signs) are kept inside words.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(is_separator)
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace()
+        || (c.is_ascii() && !c.is_ascii_alphanumeric())
+        || matches!(c, '“' | '”' | '‘' | '’' | '—' | '–' | '…' | '·' | '¶')
+}
+
+/// Score a verse against the query terms; `None` when any term is missing.
+/// Whole-word hits outweigh prefix hits, and the exact phrase earns a bonus.
+pub fn score_verse(text: &str, terms: &[String]) -> Option<u32> {
+    let tokens = tokenize(text);
+    let mut score = 0;
+    for term in terms {
+        let exact = tokens.iter().filter(|t| *t == term).count() as u32;
+        let prefix = tokens.iter().filter(|t| t.starts_with(term.as_str())).count() as u32;
+        if prefix == 0 {
+            return None;
+        }
+        score += exact * 3 + (prefix - exact);
+    }
+    if terms.len() > 1 && tokens.windows(terms.len()).any(|w| w == terms) {
+        score += 10;
+    }
+    Some(score)
+}
+
+/// Rank matching verses and wrap them with book names into a `SearchResult`
+pub fn rank_verses<I>(verses: I, query: &str, books: &[Book], translation_id: &str) -> SearchResult
+where
+    I: IntoIterator<Item = Verse>,
+{
+    let terms = tokenize(query);
+    let book_by_id: HashMap<u32, &Book> = books.iter().map(|b| (b.id, b)).collect();
+
+    let mut hits: Vec<(u32, Verse)> = if terms.is_empty() {
+        Vec::new()
+    } else {
+        verses
+            .into_iter()
+            .filter_map(|v| score_verse(&v.text, &terms).map(|score| (score, v)))
+            .collect()
+    };
+
+    let order_of = |v: &Verse| book_by_id.get(&v.book_id).map(|b| b.order_index).unwrap_or(u32::MAX);
+    hits.sort_by(|(sa, a), (sb, b)| {
+        sb.cmp(sa)
+            .then_with(|| order_of(a).cmp(&order_of(b)))
+            .then_with(|| a.chapter.cmp(&b.chapter))
+            .then_with(|| a.verse.cmp(&b.verse))
+    });
+
+    let total_count = hits.len();
+    let verses = hits
+        .into_iter()
+        .take(SEARCH_RESULT_LIMIT)
+        .map(|(_, verse)| {
+            let book = book_by_id.get(&verse.book_id);
+            VerseWithBook {
+                book_name: book.map(|b| b.display_name().to_string()).unwrap_or_default(),
+                book_abbreviation: book.map(|b| b.abbreviation.clone()).unwrap_or_default(),
+                verse,
+            }
+        })
+        .collect();
+
+    SearchResult {
+        verses,
+        total_count,
+        query: query.to_string(),
+        translation_id: translation_id.to_string(),
+    }
+}
+
+/// Word-level inverted index over one translation, persisted next to its XML.
+/// Holds verse references only; hit texts are read back from the translation's chapter store.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    source: SourceStamp,
+    /// (book_id, chapter, verse) of each indexed verse, in store order
+    docs: Vec<(u32, u32, u32)>,
+    /// term -> sorted positions in `docs`
+    postings: BTreeMap<String, Vec<u32>>,
+}
+
+impl SearchIndex {
+    pub fn build(verses: &[Verse], source: SourceStamp) -> Self {
+        let mut postings: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        let mut docs = Vec::with_capacity(verses.len());
+        for (pos, v) in verses.iter().enumerate() {
+            let pos = pos as u32;
+            for term in tokenize(&v.text) {
+                let list = postings.entry(term).or_default();
+                if list.last() != Some(&pos) {
+                    list.push(pos);
+                }
+            }
+            docs.push((v.book_id, v.chapter, v.verse));
+        }
+        Self { version: INDEX_VERSION, source, docs, postings }
+    }
+
+    /// Whether this index was built by the current code from the given source file
+    pub fn is_current(&self, source: &SourceStamp) -> bool {
+        self.version == INDEX_VERSION && &self.source == source
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BibleError> {
+        let bytes = std::fs::read(path).map_err(|e| BibleError::io("read", path, e))?;
+        serde_json::from_slice(&bytes).map_err(|e| BibleError::json(path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BibleError> {
+        let bytes = serde_json::to_vec(self).map_err(|e| BibleError::serialize(path, e))?;
+        let tmp = super::temp_path(path);
+        std::fs::write(&tmp, bytes).map_err(|e| BibleError::io("write", &tmp, e))?;
+        std::fs::rename(&tmp, path).map_err(|e| BibleError::io("write", path, e))
+    }
+
+    /// Verses containing every query term (as a word or word prefix), ranked; `store` must be
+    /// the one the index was built from
+    pub fn search(&self, store: &ChapterStore, query: &str, books: &[Book], translation_id: &str) -> Result<SearchResult, BibleError> {
+        let terms = tokenize(query);
+
+        let mut candidates: Option<Vec<u32>> = None;
+        for term in &terms {
+            let mut matches: Vec<u32> = self
+                .postings
+                .range(term.clone()..)
+                .take_while(|(word, _)| word.starts_with(term.as_str()))
+                .flat_map(|(_, list)| list.iter().copied())
+                .collect();
+            matches.sort_unstable();
+            matches.dedup();
+            candidates = Some(match candidates {
+                None => matches,
+                Some(prev) => prev.into_iter().filter(|p| matches.binary_search(p).is_ok()).collect(),
+            });
+        }
+
+        // Positions follow store order, so each chapter's hits are read together
+        let mut verses = Vec::new();
+        let mut chapter: Option<((u32, u32), Vec<Verse>)> = None;
+        for pos in candidates.unwrap_or_default() {
+            let (book_id, chapter_num, verse) = self.docs[pos as usize];
+            if chapter.as_ref().is_none_or(|(key, _)| *key != (book_id, chapter_num)) {
+                chapter = Some(((book_id, chapter_num), store.read_chapter(translation_id, book_id, chapter_num)?));
+            }
+            if let Some((_, list)) = &chapter {
+                verses.extend(list.iter().find(|v| v.verse == verse).cloned());
+            }
+        }
+        Ok(rank_verses(verses, query, books, translation_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::formats::ParsedText;
+    use super::super::testing::{scratch_path, verse, write_store};
+    use super::*;
+
+    fn verses() -> Vec<Verse> {
+        vec![
+            verse("t", 43, 3, 16, "For God so loved the world, that he gave his only begotten Son"),
+            verse("t", 62, 4, 8, "He that loveth not knoweth not God; for God is love."),
+            verse("t", 1, 1, 1, "In the beginning God created the heaven and the earth."),
+            verse("t", 19, 23, 1, "The LORD is my shepherd; I shall not want."),
+            verse("t", 1, 1, 2, "ஆதியிலே தேவன் வானத்தையும் பூமியையும் சிருஷ்டித்தார்."),
+        ]
+    }
+
+    fn refs(result: &SearchResult) -> Vec<(u32, u32, u32)> {
+        result.verses.iter().map(|v| (v.verse.book_id, v.verse.chapter, v.verse.verse)).collect()
+    }
+
+    #[test]
+    fn tokens_are_lowercase_words_with_marks_kept() {
+        assert_eq!(tokenize("“God’s love,” he said—"), ["god", "s", "love", "he", "said"]);
+        assert_eq!(tokenize("தேவன் வானத்தையும்"), ["தேவன்", "வானத்தையும்"]);
+        assert!(tokenize(" ;; ").is_empty());
+    }
+
+    #[test]
+    fn whole_words_and_phrases_score_higher() {
+        let terms = tokenize("god love");
+        assert_eq!(score_verse("Shepherd", &terms), None);
+        let phrase = score_verse("God love", &terms).unwrap();
+        let words = score_verse("love of God", &terms).unwrap();
+        let prefixes = score_verse("Godly loveth", &terms).unwrap();
+        assert!(phrase > words && words > prefixes, "{} {} {}", phrase, words, prefixes);
+    }
+
+    #[test]
+    fn ranks_by_score_then_canonical_order() {
+        let books = super::super::catalogue::books();
+        let result = rank_verses(verses(), "god", &books, "t");
+        // 1 John 4:8 names God twice; the rest once, in book order
+        assert_eq!(refs(&result), [(62, 4, 8), (1, 1, 1), (43, 3, 16)]);
+        assert_eq!(result.total_count, 3);
+        assert_eq!(result.verses[1].book_name, "Genesis");
+        assert!(rank_verses(verses(), "  ", &books, "t").verses.is_empty());
+    }
+
+    #[test]
+    fn index_finds_what_a_scan_finds() {
+        let books = super::super::catalogue::books();
+        let stamp = SourceStamp { len: 1, modified_ms: 1 };
+        let text = ParsedText { verses: verses(), book_names: HashMap::new() };
+        let store = write_store("search-scan.store", &text, &stamp);
+        let index = SearchIndex::build(&store.all_verses("t").unwrap(), stamp);
+        for query in ["god", "lov", "not god", "தேவ", "the lord", "nothing"] {
+            let found = index.search(&store, query, &books, "t").unwrap();
+            assert_eq!(found, rank_verses(verses(), query, &books, "t"), "{}", query);
+        }
+        let _ = std::fs::remove_file(scratch_path("search-scan.store"));
+    }
+
+    #[test]
+    fn saved_index_loads_and_knows_its_source() {
+        let path = scratch_path("search.idx.json");
+        let stamp = SourceStamp { len: 10, modified_ms: 20 };
+        let text = ParsedText { verses: verses(), book_names: HashMap::new() };
+        let store = write_store("search-saved.store", &text, &stamp);
+        SearchIndex::build(&store.all_verses("t").unwrap(), stamp.clone()).save(&path).unwrap();
+
+        let index = SearchIndex::load(&path).unwrap();
+        assert!(index.is_current(&stamp));
+        assert!(!index.is_current(&SourceStamp { len: 11, modified_ms: 20 }));
+        // Only references are saved; the text comes back from the store
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("my shepherd"));
+        assert_eq!(index.search(&store, "shepherd", &[], "t").unwrap().verses[0].verse.text, verses()[3].text);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(scratch_path("search-saved.store"));
+    }
+}
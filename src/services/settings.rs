@@ -0,0 +1,59 @@
+//! User settings, kept in `settings.json` under the app data directory. Every field has a
+//! default, so a missing file, or one written by an older version, still loads.
+
+use super::app_data_dir;
+use crate::error::BibleError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The published HB_index
+pub const DEFAULT_INDEX_URL: &str =
+    "https://raw.githubusercontent.com/SujithChristopher/HB_index/master/bible-translations-index.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Translation indexes to try in order: http(s) URLs, or file paths (relative ones are taken
+    /// from the app data directory). The index bundled with the app is always tried last.
+    pub index_urls: Vec<String>,
+    /// Base URLs that relative `download_url`s resolve against, in order; later ones are used
+    /// when a download from an earlier one fails. Empty means resolve against the index's URL.
+    pub mirrors: Vec<String>,
+    /// Translation to open at startup, chosen on first run
+    pub default_translation: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { index_urls: vec![DEFAULT_INDEX_URL.to_string()], mirrors: Vec::new(), default_translation: None }
+    }
+}
+
+impl Settings {
+    /// Settings from disk, or the defaults if there are none or they can't be read
+    pub fn load() -> Self {
+        let Ok(path) = settings_path() else {
+            return Self::default();
+        };
+        match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                eprintln!("[BibleService] Ignoring unreadable {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), BibleError> {
+        let path = settings_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| BibleError::io("create", dir, e))?;
+        }
+        let bytes = serde_json::to_vec_pretty(self).map_err(|e| BibleError::serialize(&path, e))?;
+        std::fs::write(&path, bytes).map_err(|e| BibleError::io("write", &path, e))
+    }
+}
+
+fn settings_path() -> Result<PathBuf, BibleError> {
+    Ok(app_data_dir()?.join("settings.json"))
+}
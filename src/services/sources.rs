@@ -0,0 +1,712 @@
+//! Where Bible data comes from. `BibleService` asks its sources in priority order and uses the
+//! first one that has an answer; a source answers `Ok(None)` for translations it doesn't hold.
+
+use super::download::RemoteFile;
+use super::index_cache;
+use super::settings::Settings;
+use super::search::SearchIndex;
+use super::store::ChapterStore;
+use super::*;
+use crate::error::BibleError;
+use async_trait::async_trait;
+use reqwest::header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{StatusCode, Url};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[async_trait]
+pub trait BibleSource: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Translations this source can offer, in the order it wants them shown
+    async fn list_translations(&mut self) -> Result<Vec<Translation>, BibleError>;
+
+    /// Books of a translation, or `None` if this source doesn't hold it
+    async fn list_books(&mut self, translation_id: &str) -> Result<Option<Vec<Book>>, BibleError>;
+
+    /// Verses of one chapter, or `None` if this source doesn't have that chapter
+    async fn fetch_chapter(&mut self, translation_id: &str, book_id: u32, chapter: u32) -> Result<Option<Vec<Verse>>, BibleError>;
+
+    /// Ranked search over a translation; `books` names the books in the results
+    async fn search(&mut self, translation_id: &str, query: &str, books: &[Book]) -> Result<Option<SearchResult>, BibleError>;
+
+    /// Where the translation's source file can be downloaded from, if this source knows
+    fn remote_file(&self, _translation_id: &str) -> Option<RemoteFile> {
+        None
+    }
+}
+
+/// Where a translations index can be read from
+#[derive(Debug, Clone)]
+pub enum IndexLocation {
+    /// A checked-out copy of HB_index (or any index file) on disk
+    File(PathBuf),
+    /// An index served over HTTP
+    Url(String),
+    /// `data/translations_index.json` compiled into the app
+    Bundled,
+}
+
+impl IndexLocation {
+    /// An entry of `Settings::index_urls`: an http(s) URL, or a file path taken from the app
+    /// data directory when relative (never from the working directory)
+    pub fn from_setting(entry: &str) -> Result<Self, BibleError> {
+        let entry = entry.trim();
+        if entry.starts_with("http://") || entry.starts_with("https://") {
+            return Ok(IndexLocation::Url(entry.to_string()));
+        }
+        let path = PathBuf::from(entry);
+        Ok(IndexLocation::File(if path.is_absolute() { path } else { app_data_dir()?.join(path) }))
+    }
+
+    /// What relative `download_url`s in this index are relative to, when no mirrors are set
+    fn base_url(&self) -> Option<Url> {
+        match self {
+            IndexLocation::Url(url) => Url::parse(url).ok(),
+            IndexLocation::File(_) | IndexLocation::Bundled => None,
+        }
+    }
+}
+
+/// How long to wait for an index server before falling back to the cached copy
+const INDEX_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Translations index: the first location that loads wins, later ones are fallbacks.
+/// Holds no verse text, only listings and download URLs. The index is read once per session;
+/// URL locations go through the on-disk cache in `index_cache`.
+pub struct RemoteIndexSource {
+    locations: Vec<IndexLocation>,
+    /// Bases for relative download URLs, in fallback order
+    mirrors: Vec<Url>,
+    downloads: HashMap<String, RemoteFile>,
+    listing: Option<Vec<Translation>>,
+}
+
+impl RemoteIndexSource {
+    pub fn new(locations: Vec<IndexLocation>) -> Self {
+        Self { locations, mirrors: Vec::new(), downloads: HashMap::new(), listing: None }
+    }
+
+    /// The index URLs and mirrors from the user's settings, then the bundled list
+    pub fn from_settings(settings: &Settings) -> Self {
+        let mut locations: Vec<IndexLocation> = settings
+            .index_urls
+            .iter()
+            .filter_map(|entry| match IndexLocation::from_setting(entry) {
+                Ok(location) => Some(location),
+                Err(e) => {
+                    eprintln!("[BibleService] Skipping index {}: {}", entry, e);
+                    None
+                }
+            })
+            .collect();
+        locations.push(IndexLocation::Bundled);
+        Self::new(locations).with_mirrors(&settings.mirrors)
+    }
+
+    pub fn with_mirrors(mut self, mirrors: &[String]) -> Self {
+        self.mirrors = mirrors
+            .iter()
+            .filter_map(|m| {
+                // A base without a trailing slash would lose its last path segment when joined
+                let base = if m.ends_with('/') { m.clone() } else { format!("{}/", m) };
+                Url::parse(&base).map_err(|e| eprintln!("[BibleService] Skipping mirror {}: {}", m, e)).ok()
+            })
+            .collect();
+        self
+    }
+
+    async fn load(&self, location: &IndexLocation) -> Result<(Vec<Translation>, HashMap<String, RemoteFile>), BibleError> {
+        let (translations, downloads) = self.load_unresolved(location).await?;
+        let downloads = downloads
+            .into_iter()
+            .filter_map(|(id, remote)| self.resolve(remote, location.base_url()).map(|remote| (id, remote)))
+            .collect();
+        Ok((translations, downloads))
+    }
+
+    /// Absolute download URLs are kept as they are. Relative ones are resolved against each
+    /// mirror in turn, the first becoming the URL and the rest its fallbacks, or against the
+    /// index's own URL when there are no mirrors. Unresolvable entries can't be downloaded.
+    fn resolve(&self, mut remote: RemoteFile, index_base: Option<Url>) -> Option<RemoteFile> {
+        if Url::parse(&remote.url).is_ok() {
+            return Some(remote);
+        }
+        let bases: Vec<Url> = if self.mirrors.is_empty() { index_base.into_iter().collect() } else { self.mirrors.clone() };
+        let mut urls: Vec<String> = bases.iter().filter_map(|base| base.join(&remote.url).ok()).map(String::from).collect();
+        if urls.is_empty() {
+            return None;
+        }
+        remote.url = urls.remove(0);
+        remote.mirrors = urls;
+        Some(remote)
+    }
+
+    async fn load_unresolved(&self, location: &IndexLocation) -> Result<(Vec<Translation>, HashMap<String, RemoteFile>), BibleError> {
+        match location {
+            IndexLocation::File(path) => {
+                let bytes = fs::read(path).await.map_err(|e| BibleError::io("read", path, e))?;
+                let hb_index: HbIndex = serde_json::from_slice(&bytes).map_err(|e| BibleError::json(path.display(), e))?;
+                Ok(translations_from_hb_index(hb_index))
+            }
+            IndexLocation::Url(url) => Self::fetch(url).await,
+            IndexLocation::Bundled => {
+                let translations_json = include_str!("../data/translations_index.json");
+                let data = serde_json::from_str::<TranslationIndex>(translations_json)
+                    .map_err(|e| BibleError::json("bundled translations index", e))?;
+                Ok((data.translations, HashMap::new()))
+            }
+        }
+    }
+
+    /// The index at `url`: straight from the cache while it's fresh, otherwise revalidated with
+    /// the server, and from a stale cached copy if the server can't be reached
+    async fn fetch(url: &str) -> Result<(Vec<Translation>, HashMap<String, RemoteFile>), BibleError> {
+        let cached = index_cache::read(url);
+        if let Some(cached) = cached.as_ref().filter(|c| c.is_fresh()) {
+            if let Ok(parsed) = Self::parse(&cached.bytes) {
+                return Ok(parsed);
+            }
+        }
+        match Self::revalidate(url, cached.as_ref()).await {
+            Ok(parsed) => Ok(parsed),
+            Err(e) => match cached.as_ref().and_then(|c| Self::parse(&c.bytes).ok()) {
+                Some(parsed) => {
+                    eprintln!("[BibleService] Index {} unreachable ({}), using cached copy", url, e);
+                    Ok(parsed)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    async fn revalidate(url: &str, cached: Option<&index_cache::CachedIndex>) -> Result<(Vec<Translation>, HashMap<String, RemoteFile>), BibleError> {
+        let client = Client::builder().timeout(INDEX_TIMEOUT).build()?;
+        let mut request = client.get(url);
+        if let Some(cached) = cached {
+            if let Some(etag) = cached.etag() {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = cached.last_modified() {
+                request = request.header(IF_MODIFIED_SINCE, modified);
+            }
+        }
+        let resp = request.send().await?;
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (resp.status(), cached) {
+            let parsed = Self::parse(&cached.bytes)?;
+            if let Err(e) = index_cache::touch(cached) {
+                eprintln!("[BibleService] Failed to update index cache: {}", e);
+            }
+            return Ok(parsed);
+        }
+
+        let resp = resp.error_for_status()?;
+        let header = |name: HeaderName| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let bytes = resp.bytes().await?;
+        // Only keep what parses, so a broken response can't replace a good copy
+        let parsed = Self::parse(&bytes)?;
+        if let Err(e) = index_cache::write(url, &bytes, etag, last_modified) {
+            eprintln!("[BibleService] Failed to cache index: {}", e);
+        }
+        Ok(parsed)
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Vec<Translation>, HashMap<String, RemoteFile>), BibleError> {
+        // Try new HB_index format first
+        if let Ok(hb_index) = serde_json::from_slice::<HbIndex>(bytes) {
+            return Ok(translations_from_hb_index(hb_index));
+        }
+
+        // Fallback to legacy format
+        let entries = parse_hb_entries(bytes)?;
+        let translations = entries
+            .iter()
+            .map(|e| Translation {
+                id: e.id.clone(),
+                name: e.name.clone(),
+                abbreviation: e.abbr.clone().unwrap_or_else(|| extract_abbreviation(&e.name)),
+                language: e.lang.clone().unwrap_or_else(|| "unknown".into()),
+                language_name: e.lang_name.clone(),
+                description: e.description.clone().unwrap_or_else(|| e.name.clone()),
+                bundled: false,
+                priority: 0,
+                versification: Versification::default(),
+                coverage: None,
+            })
+            .collect::<Vec<_>>();
+        let downloads = entries
+            .into_iter()
+            .filter_map(|e| e.download_url.map(|url| (e.id, RemoteFile { url, mirrors: Vec::new(), size: None, sha256: None, year: None, status: None })))
+            .collect();
+        Ok((translations, downloads))
+    }
+}
+
+impl Default for RemoteIndexSource {
+    /// Whatever `settings.json` lists (the published HB_index unless changed), then the bundled list
+    fn default() -> Self {
+        Self::from_settings(&Settings::load())
+    }
+}
+
+#[async_trait]
+impl BibleSource for RemoteIndexSource {
+    fn name(&self) -> &'static str {
+        "index"
+    }
+
+    async fn list_translations(&mut self) -> Result<Vec<Translation>, BibleError> {
+        if let Some(listing) = &self.listing {
+            return Ok(listing.clone());
+        }
+        let mut last_error = BibleError::NoTranslations;
+        for location in &self.locations {
+            match self.load(location).await {
+                Ok((translations, downloads)) => {
+                    self.downloads = downloads;
+                    self.listing = Some(translations.clone());
+                    return Ok(translations);
+                }
+                Err(e) => {
+                    eprintln!("[BibleService] Index {:?} failed: {}. Trying next.", location, e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    async fn list_books(&mut self, _translation_id: &str) -> Result<Option<Vec<Book>>, BibleError> {
+        Ok(None)
+    }
+
+    async fn fetch_chapter(&mut self, _translation_id: &str, _book_id: u32, _chapter: u32) -> Result<Option<Vec<Verse>>, BibleError> {
+        Ok(None)
+    }
+
+    async fn search(&mut self, _translation_id: &str, _query: &str, _books: &[Book]) -> Result<Option<SearchResult>, BibleError> {
+        Ok(None)
+    }
+
+    fn remote_file(&self, translation_id: &str) -> Option<RemoteFile> {
+        self.downloads.get(translation_id).cloned()
+    }
+}
+
+fn translations_from_hb_index(hb_index: HbIndex) -> (Vec<Translation>, HashMap<String, RemoteFile>) {
+    let mut downloads = HashMap::new();
+    let mut translations = Vec::new();
+
+    for lang in hb_index.languages {
+        for trans in lang.translations {
+            translations.push(Translation {
+                id: trans.id.clone(),
+                name: trans.name.clone(),
+                abbreviation: extract_abbreviation(&trans.name),
+                language: lang.iso_code.clone().unwrap_or_else(|| lang.language.clone().to_lowercase()),
+                language_name: Some(lang.native_name.clone().unwrap_or(lang.language.clone())),
+                description: trans.metadata.as_ref()
+                    .and_then(|m| m.info.clone())
+                    .unwrap_or_else(|| trans.name.clone()),
+                bundled: false,
+                priority: 0,
+                versification: trans.versification,
+                coverage: trans.testament_coverage.as_ref().map(|c| TestamentCoverage {
+                    old_testament: c.old_testament,
+                    new_testament: c.new_testament,
+                }),
+            });
+            if let Some(url) = trans.download_url {
+                let (year, status) = trans.metadata.map(|m| (m.year, m.status)).unwrap_or_default();
+                downloads.insert(trans.id, RemoteFile { url, mirrors: Vec::new(), size: trans.file_size_bytes, sha256: trans.sha256, year, status });
+            }
+        }
+    }
+    (translations, downloads)
+}
+
+/// Chapter stores already built under `translations/`. A store is served while it matches its
+/// source file, or on its own if the source has been removed; stale stores are left to
+/// `LocalFileSource` to rebuild.
+#[derive(Default)]
+pub struct StoreSource {
+    stores: HashMap<String, ChapterStore>,
+    search_indexes: HashMap<String, SearchIndex>,
+}
+
+impl StoreSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The open store for a translation, if there is a usable one on disk
+    fn store(&mut self, translation_id: &str) -> Result<Option<&ChapterStore>, BibleError> {
+        let stamp = match source_for_translation(translation_id)? {
+            Some((path, _)) => Some(SourceStamp::of(&path)?),
+            None => None,
+        };
+        let usable = |s: &ChapterStore| stamp.as_ref().is_none_or(|stamp| s.is_current(stamp));
+
+        if !self.stores.get(translation_id).is_some_and(usable) {
+            self.stores.remove(translation_id);
+            self.search_indexes.remove(translation_id);
+            match ChapterStore::open(&store_path_for_translation(translation_id)?) {
+                Ok(store) if usable(&store) => {
+                    self.stores.insert(translation_id.to_string(), store);
+                }
+                _ => return Ok(None),
+            }
+        }
+        Ok(self.stores.get(translation_id))
+    }
+}
+
+#[async_trait]
+impl BibleSource for StoreSource {
+    fn name(&self) -> &'static str {
+        "store"
+    }
+
+    /// Stores carry no names; the index and local files list what they hold
+    async fn list_translations(&mut self) -> Result<Vec<Translation>, BibleError> {
+        Ok(Vec::new())
+    }
+
+    async fn list_books(&mut self, translation_id: &str) -> Result<Option<Vec<Book>>, BibleError> {
+        Ok(self.store(translation_id)?.map(books_from_store).filter(|b| !b.is_empty()))
+    }
+
+    async fn fetch_chapter(&mut self, translation_id: &str, book_id: u32, chapter: u32) -> Result<Option<Vec<Verse>>, BibleError> {
+        let Some(store) = self.store(translation_id)? else {
+            return Ok(None);
+        };
+        let list = store.read_chapter(translation_id, book_id, chapter)?;
+        Ok(Some(list).filter(|l| !l.is_empty()))
+    }
+
+    async fn search(&mut self, translation_id: &str, query: &str, books: &[Book]) -> Result<Option<SearchResult>, BibleError> {
+        if self.store(translation_id)?.is_none() {
+            return Ok(None);
+        }
+        if !self.search_indexes.contains_key(translation_id) {
+            let index = load_or_build_index(&self.stores[translation_id], translation_id)?;
+            self.search_indexes.insert(translation_id.to_string(), index);
+        }
+        let store = &self.stores[translation_id];
+        Ok(Some(self.search_indexes[translation_id].search(store, query, books, translation_id)?))
+    }
+}
+
+/// Source files under `translations/`: downloaded or imported XML and USFM/USX directories.
+/// Lists sideloaded and otherwise unindexed translations, and (re)imports a source into its
+/// chapter store whenever the store is missing or older than the file.
+#[derive(Default)]
+pub struct LocalFileSource {
+    search_indexes: HashMap<String, SearchIndex>,
+}
+
+impl LocalFileSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the translation's chapter store, importing its source first if needed
+    fn store(&self, translation_id: &str) -> Result<Option<ChapterStore>, BibleError> {
+        open_or_import_store(translation_id)
+    }
+}
+
+#[async_trait]
+impl BibleSource for LocalFileSource {
+    fn name(&self) -> &'static str {
+        "local files"
+    }
+
+    /// Sideloaded translations from the local registry first, then any other source files found,
+    /// described from the metadata the file carries where possible
+    async fn list_translations(&mut self) -> Result<Vec<Translation>, BibleError> {
+        let dir = app_data_dir()?.join("translations");
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+
+        let mut registered = read_local_registry()?;
+        registered.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut found: Vec<Translation> = Vec::new();
+        for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+            let (Some(id), Some(ext)) = (
+                path.file_stem().and_then(|s| s.to_str()),
+                path.extension().and_then(|s| s.to_str()),
+            ) else {
+                continue;
+            };
+            let Some(kind) = SourceKind::ALL.into_iter().find(|k| k.extension() == ext) else {
+                continue;
+            };
+            if registered.iter().chain(&found).any(|t| t.id == id) {
+                continue;
+            }
+
+            let info = match kind {
+                SourceKind::Xml => match formats::detect(&path) {
+                    Ok(format) => format.info(&path).unwrap_or_default(),
+                    // Not something we can open; don't offer it
+                    Err(_) => continue,
+                },
+                _ => formats::FileInfo::default(),
+            };
+            let name = info.title.unwrap_or_else(|| id.to_string());
+            found.push(Translation {
+                id: id.to_string(),
+                abbreviation: extract_abbreviation(&name),
+                language: info.language.as_deref().map(str::to_lowercase).unwrap_or_else(|| "unknown".into()),
+                language_name: info.language,
+                description: name.clone(),
+                name,
+                bundled: false,
+                priority: 0,
+                versification: Versification::default(),
+                coverage: None,
+            });
+        }
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+        registered.extend(found);
+        Ok(registered)
+    }
+
+    async fn list_books(&mut self, translation_id: &str) -> Result<Option<Vec<Book>>, BibleError> {
+        Ok(self.store(translation_id)?.as_ref().map(books_from_store).filter(|b| !b.is_empty()))
+    }
+
+    async fn fetch_chapter(&mut self, translation_id: &str, book_id: u32, chapter: u32) -> Result<Option<Vec<Verse>>, BibleError> {
+        let Some(store) = self.store(translation_id)? else {
+            return Ok(None);
+        };
+        let list = store.read_chapter(translation_id, book_id, chapter)?;
+        Ok(Some(list).filter(|l| !l.is_empty()))
+    }
+
+    async fn search(&mut self, translation_id: &str, query: &str, books: &[Book]) -> Result<Option<SearchResult>, BibleError> {
+        let Some(store) = self.store(translation_id)? else {
+            return Ok(None);
+        };
+        if !self.search_indexes.get(translation_id).is_some_and(|idx| idx.is_current(store.source())) {
+            let index = load_or_build_index(&store, translation_id)?;
+            self.search_indexes.insert(translation_id.to_string(), index);
+        }
+        Ok(Some(self.search_indexes[translation_id].search(&store, query, books, translation_id)?))
+    }
+}
+
+/// Fixed in-memory translations, to test the source chain without touching disk or network
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemorySource {
+    translations: Vec<Translation>,
+    books: HashMap<String, Vec<Book>>,
+    verses: HashMap<String, Vec<Verse>>,
+}
+
+#[cfg(test)]
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a translation with its books and verses; books default to those the verses use
+    pub fn with_translation(mut self, translation: Translation, books: Option<Vec<Book>>, verses: Vec<Verse>) -> Self {
+        let books = books.unwrap_or_else(|| {
+            let mut list: Vec<Book> = catalogue::books()
+                .into_iter()
+                .filter(|b| verses.iter().any(|v| v.book_id == b.id))
+                .map(|b| Book {
+                    chapter_count: verses.iter().filter(|v| v.book_id == b.id).map(|v| v.chapter).max().unwrap_or(0),
+                    ..b
+                })
+                .collect();
+            list.sort_by_key(|b| b.order_index);
+            list
+        });
+        self.books.insert(translation.id.clone(), books);
+        self.verses.insert(translation.id.clone(), verses);
+        self.translations.push(translation);
+        self
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl BibleSource for MemorySource {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn list_translations(&mut self) -> Result<Vec<Translation>, BibleError> {
+        Ok(self.translations.clone())
+    }
+
+    async fn list_books(&mut self, translation_id: &str) -> Result<Option<Vec<Book>>, BibleError> {
+        Ok(self.books.get(translation_id).cloned())
+    }
+
+    async fn fetch_chapter(&mut self, translation_id: &str, book_id: u32, chapter: u32) -> Result<Option<Vec<Verse>>, BibleError> {
+        let Some(verses) = self.verses.get(translation_id) else {
+            return Ok(None);
+        };
+        let list: Vec<Verse> = verses
+            .iter()
+            .filter(|v| v.book_id == book_id && v.chapter == chapter)
+            .cloned()
+            .collect();
+        Ok(Some(list).filter(|l| !l.is_empty()))
+    }
+
+    async fn search(&mut self, translation_id: &str, query: &str, books: &[Book]) -> Result<Option<SearchResult>, BibleError> {
+        Ok(self
+            .verses
+            .get(translation_id)
+            .map(|verses| super::search::rank_verses(verses.iter().cloned(), query, books, translation_id)))
+    }
+}
+
+/// Books present in a chapter store, in the order the source has them. Testament and English
+/// names come from the book catalogue; a name the source gives that differs from the English one
+/// becomes the localized name.
+fn books_from_store(store: &ChapterStore) -> Vec<Book> {
+    store
+        .books()
+        .iter()
+        .filter_map(|stored| catalogue::book(stored.book_id).map(|book| (stored, book)))
+        .enumerate()
+        .map(|(position, (stored, book))| Book {
+            order_index: position as u32 + 1,
+            chapter_count: stored.chapter_count,
+            localized_name: stored.name.clone().filter(|name| *name != book.name),
+            ..book
+        })
+        .collect()
+}
+
+/// The saved search index if it was built from the same source as the store, otherwise a fresh one
+fn load_or_build_index(store: &ChapterStore, translation_id: &str) -> Result<SearchIndex, BibleError> {
+    let index_path = index_path_for_translation(translation_id)?;
+    match SearchIndex::load(&index_path) {
+        Ok(idx) if idx.is_current(store.source()) => Ok(idx),
+        _ => {
+            let index = SearchIndex::build(&store.all_verses(translation_id)?, store.source().clone());
+            index.save(&index_path)?;
+            Ok(index)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{translation, verse};
+    use super::*;
+
+    fn memory(id: &str, verses: &[(u32, u32, u32, &str)]) -> Box<dyn BibleSource> {
+        let verses = verses.iter().map(|&(b, c, v, text)| verse(id, b, c, v, text)).collect();
+        Box::new(MemorySource::new().with_translation(translation(id), None, verses))
+    }
+
+    #[test]
+    fn default_chain_is_index_store_then_local_files() {
+        let service = BibleService::with_settings(&Settings::default());
+        let names: Vec<&str> = service.sources.iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["index", "store", "local files"]);
+    }
+
+    #[tokio::test]
+    async fn first_source_with_the_chapter_wins() {
+        let mut service = BibleService::with_sources(vec![
+            memory("kjv", &[(1, 1, 1, "first")]),
+            memory("kjv", &[(1, 1, 1, "second"), (1, 2, 1, "only in second")]),
+        ]);
+        assert_eq!(service.load_verses("kjv", 1, 1).await.unwrap()[0].text, "first");
+        // The first source doesn't hold chapter 2, so the next one answers
+        assert_eq!(service.load_verses("kjv", 1, 2).await.unwrap()[0].text, "only in second");
+    }
+
+    #[tokio::test]
+    async fn higher_priority_listing_is_kept() {
+        let mut first = translation("kjv");
+        first.name = "From first".into();
+        let mut service = BibleService::with_sources(vec![
+            Box::new(MemorySource::new().with_translation(first, None, Vec::new())),
+            memory("kjv", &[]),
+            memory("web", &[]),
+        ]);
+        let listed = service.load_translations().await.unwrap();
+        let names: Vec<(&str, &str)> = listed.iter().map(|t| (t.id.as_str(), t.name.as_str())).collect();
+        assert_eq!(names, [("kjv", "From first"), ("web", "WEB")]);
+    }
+
+    #[tokio::test]
+    async fn missing_text_falls_through_to_the_right_error() {
+        let mut service = BibleService::with_sources(vec![memory("kjv", &[(1, 1, 1, "In the beginning")])]);
+        assert!(matches!(
+            service.load_verses("web", 1, 1).await,
+            Err(BibleError::NotDownloaded(id)) if id == "web"
+        ));
+        assert!(matches!(service.load_verses("kjv", 2, 1).await, Err(BibleError::UnknownBook { book_id: 2, .. })));
+        assert!(matches!(service.load_verses("kjv", 1, 3).await, Err(BibleError::MissingChapter { chapter: 3, .. })));
+    }
+
+    /// A relative download URL resolved against `mirrors`, ready to download into a temp dir
+    fn mirrored_job(name: &str, mirrors: &[String], size: usize) -> DownloadJob {
+        let source = RemoteIndexSource::new(Vec::new()).with_mirrors(mirrors);
+        let remote = RemoteFile { url: "bible.xml".into(), mirrors: Vec::new(), size: Some(size as u64), sha256: None, year: None, status: None };
+        let mut job = super::download::tests::job(name, "", size);
+        job.remote = source.resolve(remote, None).unwrap();
+        job
+    }
+
+    fn base(url: &str) -> String {
+        url.trim_end_matches("bible.xml").to_string()
+    }
+
+    #[tokio::test]
+    async fn failing_mirror_is_retried_then_the_next_one_serves() {
+        use super::download::tests::{file_bytes, serve, Reply};
+        let file = file_bytes(10_000);
+        let broken: Vec<Reply> = (0..super::download::MAX_ATTEMPTS)
+            .map(|_| Reply::new("500 Internal Server Error", &[("Content-Length", "0".into())], b""))
+            .collect();
+        let (broken_url, broken_requests) = serve(broken).await;
+        let (good_url, good_requests) = serve(vec![Reply::new("200 OK", &[("Content-Length", file.len().to_string())], &file)]).await;
+        let job = mirrored_job("mirror-500", &[base(&broken_url), base(&good_url)], file.len());
+        assert_eq!(job.remote.url, broken_url);
+        assert_eq!(job.remote.mirrors, [good_url]);
+
+        let dest = job.run(&CancelToken::new(), |_| {}).await.unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), file);
+        assert_eq!(broken_requests.lock().unwrap().len(), super::download::MAX_ATTEMPTS as usize);
+        assert_eq!(good_requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn refused_mirror_falls_back_to_the_next_one() {
+        use super::download::tests::{file_bytes, serve, Reply};
+        let file = file_bytes(10_000);
+        // A port that was free a moment ago refuses connections
+        let refused = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let refused_base = format!("http://{}/", refused);
+        let (good_url, good_requests) = serve(vec![Reply::new("200 OK", &[("Content-Length", file.len().to_string())], &file)]).await;
+        let job = mirrored_job("mirror-refused", &[refused_base.clone(), base(&good_url)], file.len());
+        assert_eq!(job.remote.url, format!("{}bible.xml", refused_base));
+        assert_eq!(job.remote.mirrors, [good_url]);
+
+        let dest = job.run(&CancelToken::new(), |_| {}).await.unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), file);
+        assert_eq!(good_requests.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,362 @@
+//! Compact on-disk chapter store built once per translation.
+//!
+//! Layout (little-endian):
+//! `magic | version | source stamp | books | chapter table | verse data`,
+//! where each chapter table entry points at its verses so a chapter is one seek + read.
+
+use super::formats::ParsedText;
+use super::SourceStamp;
+use crate::error::BibleError;
+use crate::types::Verse;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"SBST";
+/// Bumped whenever the layout changes so old stores are rebuilt
+const STORE_VERSION: u32 = 2;
+
+/// Book as it appears in a store: id, highest chapter number seen and the name the source gives it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredBook {
+    pub book_id: u32,
+    pub chapter_count: u32,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ChapterEntry {
+    offset: u64,
+    verse_count: u32,
+}
+
+/// Read handle over a store file; only the header and tables live in memory
+#[derive(Debug)]
+pub struct ChapterStore {
+    path: PathBuf,
+    source: SourceStamp,
+    books: Vec<StoredBook>,
+    chapters: HashMap<(u32, u32), ChapterEntry>,
+}
+
+impl ChapterStore {
+    /// Write a parsed translation (verses in source order) to `path`, replacing any existing store atomically
+    pub fn write(path: &Path, text: &ParsedText, source: &SourceStamp) -> Result<(), BibleError> {
+        // Group verses by chapter, keeping the order in which books and chapters first appear
+        let verses = &text.verses;
+        let mut books: Vec<StoredBook> = Vec::new();
+        let mut chapter_order: Vec<(u32, u32)> = Vec::new();
+        let mut by_chapter: HashMap<(u32, u32), Vec<&Verse>> = HashMap::new();
+        for v in verses {
+            match books.iter_mut().find(|b| b.book_id == v.book_id) {
+                Some(b) => b.chapter_count = b.chapter_count.max(v.chapter),
+                None => books.push(StoredBook {
+                    book_id: v.book_id,
+                    chapter_count: v.chapter,
+                    name: text.book_names.get(&v.book_id).cloned(),
+                }),
+            }
+            let key = (v.book_id, v.chapter);
+            by_chapter.entry(key).or_insert_with(|| {
+                chapter_order.push(key);
+                Vec::new()
+            }).push(v);
+        }
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut table: Vec<((u32, u32), ChapterEntry)> = Vec::with_capacity(chapter_order.len());
+        for key in &chapter_order {
+            let list = &by_chapter[key];
+            table.push((*key, ChapterEntry { offset: data.len() as u64, verse_count: list.len() as u32 }));
+            for v in list {
+                data.extend_from_slice(&v.verse.to_le_bytes());
+                data.extend_from_slice(&(v.text.len() as u32).to_le_bytes());
+                data.extend_from_slice(v.text.as_bytes());
+            }
+        }
+
+        let tmp = super::temp_path(path);
+        let write_all = || -> std::io::Result<()> {
+            let mut w = BufWriter::new(File::create(&tmp)?);
+            w.write_all(MAGIC)?;
+            w.write_all(&STORE_VERSION.to_le_bytes())?;
+            w.write_all(&source.len.to_le_bytes())?;
+            w.write_all(&source.modified_ms.to_le_bytes())?;
+            w.write_all(&(books.len() as u32).to_le_bytes())?;
+            for b in &books {
+                w.write_all(&b.book_id.to_le_bytes())?;
+                w.write_all(&b.chapter_count.to_le_bytes())?;
+                // Name length then UTF-8 bytes; zero length for none
+                let name = b.name.as_deref().unwrap_or("");
+                w.write_all(&(name.len() as u32).to_le_bytes())?;
+                w.write_all(name.as_bytes())?;
+            }
+            w.write_all(&(table.len() as u32).to_le_bytes())?;
+            for ((book_id, chapter), entry) in &table {
+                w.write_all(&book_id.to_le_bytes())?;
+                w.write_all(&chapter.to_le_bytes())?;
+                w.write_all(&entry.offset.to_le_bytes())?;
+                w.write_all(&entry.verse_count.to_le_bytes())?;
+            }
+            w.write_all(&data)?;
+            w.flush()
+        };
+        write_all().map_err(|e| BibleError::io("write", &tmp, e))?;
+        std::fs::rename(&tmp, path).map_err(|e| BibleError::io("write", path, e))
+    }
+
+    /// Open a store, reading only its header and tables
+    pub fn open(path: &Path) -> Result<Self, BibleError> {
+        let read_tables = || -> std::io::Result<Option<Self>> {
+            let file = File::open(path)?;
+            let file_len = file.metadata()?.len();
+            let mut r = BufReader::new(file);
+            let mut magic = [0u8; 4];
+            r.read_exact(&mut magic)?;
+            if &magic != MAGIC || read_u32(&mut r)? != STORE_VERSION {
+                return Ok(None);
+            }
+            let source = SourceStamp { len: read_u64(&mut r)?, modified_ms: read_u64(&mut r)? };
+
+            let book_count = read_len(&mut r, file_len)?;
+            let mut books = Vec::with_capacity(book_count);
+            for _ in 0..book_count {
+                let book_id = read_u32(&mut r)?;
+                let chapter_count = read_u32(&mut r)?;
+                let mut name = vec![0u8; read_len(&mut r, file_len)?];
+                r.read_exact(&mut name)?;
+                let name = String::from_utf8(name).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                books.push(StoredBook { book_id, chapter_count, name: (!name.is_empty()).then_some(name) });
+            }
+
+            let chapter_count = read_len(&mut r, file_len)?;
+            let mut chapters = HashMap::with_capacity(chapter_count);
+            for _ in 0..chapter_count {
+                let key = (read_u32(&mut r)?, read_u32(&mut r)?);
+                let entry = ChapterEntry { offset: read_u64(&mut r)?, verse_count: read_u32(&mut r)? };
+                chapters.insert(key, entry);
+            }
+            let table_end = r.stream_position()?;
+            // Store offsets relative to the start of the verse data
+            for entry in chapters.values_mut() {
+                entry.offset += table_end;
+            }
+
+            Ok(Some(Self { path: path.to_path_buf(), source, books, chapters }))
+        };
+        match read_tables() {
+            Ok(Some(store)) => Ok(store),
+            Ok(None) => Err(BibleError::ParseError {
+                path: path.display().to_string(),
+                pos: None,
+                message: "not a current chapter store".to_string(),
+            }),
+            Err(e) => Err(read_error(path, e)),
+        }
+    }
+
+    /// Whether this store was built from the given source file
+    pub fn is_current(&self, source: &SourceStamp) -> bool {
+        &self.source == source
+    }
+
+    /// Stamp of the source the store was built from
+    pub fn source(&self) -> &SourceStamp {
+        &self.source
+    }
+
+    pub fn books(&self) -> &[StoredBook] {
+        &self.books
+    }
+
+    /// Every (book, chapter) in the store, in source order
+    pub fn chapters(&self) -> Vec<(u32, u32)> {
+        let mut entries: Vec<(&(u32, u32), &ChapterEntry)> = self.chapters.iter().collect();
+        entries.sort_by_key(|(_, e)| e.offset);
+        entries.into_iter().map(|(k, _)| *k).collect()
+    }
+
+    /// Verses of one chapter, in source order; empty if the chapter isn't in the store
+    pub fn read_chapter(&self, translation_id: &str, book_id: u32, chapter: u32) -> Result<Vec<Verse>, BibleError> {
+        let Some(entry) = self.chapters.get(&(book_id, chapter)).copied() else {
+            return Ok(Vec::new());
+        };
+        let read = || -> std::io::Result<Vec<Verse>> {
+            let file = File::open(&self.path)?;
+            let file_len = file.metadata()?.len();
+            let mut r = BufReader::new(file);
+            r.seek(SeekFrom::Start(entry.offset))?;
+            read_verses(&mut r, file_len, entry.verse_count, translation_id, book_id, chapter)
+        };
+        read().map_err(|e| read_error(&self.path, e))
+    }
+
+    /// Every verse in the store, grouped by chapter in source order
+    pub fn all_verses(&self, translation_id: &str) -> Result<Vec<Verse>, BibleError> {
+        let mut entries: Vec<((u32, u32), ChapterEntry)> = self.chapters.iter().map(|(k, v)| (*k, *v)).collect();
+        entries.sort_by_key(|(_, e)| e.offset);
+        let read = || -> std::io::Result<Vec<Verse>> {
+            let file = File::open(&self.path)?;
+            let file_len = file.metadata()?.len();
+            let mut r = BufReader::new(file);
+            let mut out = Vec::new();
+            for ((book_id, chapter), entry) in &entries {
+                r.seek(SeekFrom::Start(entry.offset))?;
+                out.extend(read_verses(&mut r, file_len, entry.verse_count, translation_id, *book_id, *chapter)?);
+            }
+            Ok(out)
+        };
+        read().map_err(|e| read_error(&self.path, e))
+    }
+}
+
+fn read_verses<R: Read>(r: &mut R, file_len: u64, count: u32, translation_id: &str, book_id: u32, chapter: u32) -> std::io::Result<Vec<Verse>> {
+    let mut verses = Vec::with_capacity((count as usize).min(file_len as usize));
+    for _ in 0..count {
+        let verse = read_u32(r)?;
+        let mut text = vec![0u8; read_len(r, file_len)?];
+        r.read_exact(&mut text)?;
+        verses.push(Verse {
+            id: format!("{}:{}:{}:{}", translation_id, book_id, chapter, verse),
+            translation_id: translation_id.to_string(),
+            book_id,
+            chapter,
+            verse,
+            text: String::from_utf8_lossy(&text).into_owned(),
+        });
+    }
+    Ok(verses)
+}
+
+/// A length or count field; none can exceed the size of the file, so a damaged store is
+/// caught here rather than by allocating whatever it claims
+fn read_len<R: Read>(r: &mut R, file_len: u64) -> std::io::Result<usize> {
+    let len = read_u32(r)?;
+    if u64::from(len) > file_len {
+        let message = format!("length {} is past the end of the file", len);
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, message));
+    }
+    Ok(len as usize)
+}
+
+/// Bad or truncated contents mean a damaged store; anything else is an I/O failure
+fn read_error(path: &Path, e: std::io::Error) -> BibleError {
+    match e.kind() {
+        std::io::ErrorKind::InvalidData | std::io::ErrorKind::UnexpectedEof => {
+            BibleError::Corrupt { path: path.display().to_string(), reason: e.to_string() }
+        }
+        _ => BibleError::io("read", path, e),
+    }
+}
+
+fn read_u32<R: Read>(r: &mut R) -> std::io::Result<u32> {
+    let mut b = [0u8; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> std::io::Result<u64> {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b)?;
+    Ok(u64::from_le_bytes(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{scratch_path, verse, write_store};
+    use super::*;
+
+    fn text() -> ParsedText {
+        ParsedText {
+            verses: vec![
+                verse("t", 43, 3, 16, "For God so loved the world"),
+                verse("t", 43, 3, 17, "For God sent not his Son"),
+                verse("t", 1, 1, 1, "ஆதியிலே தேவன் வானத்தையும் பூமியையும் சிருஷ்டித்தார்."),
+                verse("t", 1, 2, 1, "Thus the heavens and the earth were finished"),
+                verse("t", 43, 1, 1, "In the beginning was the Word"),
+            ],
+            book_names: HashMap::from([(1, "ஆதியாகமம்".to_string())]),
+        }
+    }
+
+    #[test]
+    fn round_trips_books_names_and_chapters() {
+        let stamp = SourceStamp { len: 1234, modified_ms: 5678 };
+        let store = write_store("round-trip.store", &text(), &stamp);
+        assert!(store.is_current(&stamp));
+        assert!(!store.is_current(&SourceStamp { len: 1234, modified_ms: 0 }));
+        assert_eq!(
+            store.books(),
+            [
+                StoredBook { book_id: 43, chapter_count: 3, name: None },
+                StoredBook { book_id: 1, chapter_count: 2, name: Some("ஆதியாகமம்".to_string()) },
+            ]
+        );
+        assert_eq!(store.chapters(), [(43, 3), (1, 1), (1, 2), (43, 1)]);
+
+        let john = store.read_chapter("kjv", 43, 3).unwrap();
+        assert_eq!(john.iter().map(|v| v.verse).collect::<Vec<_>>(), [16, 17]);
+        assert_eq!(john[1].text, "For God sent not his Son");
+        assert_eq!(john[0].id, "kjv:43:3:16");
+        assert_eq!(store.read_chapter("kjv", 1, 1).unwrap()[0].text, text().verses[2].text);
+        assert!(store.read_chapter("kjv", 43, 2).unwrap().is_empty());
+
+        assert_eq!(store.all_verses("t").unwrap(), text().verses);
+        let _ = std::fs::remove_file(scratch_path("round-trip.store"));
+    }
+
+    #[test]
+    fn rewriting_replaces_the_store() {
+        write_store("rewrite.store", &text(), &SourceStamp { len: 1, modified_ms: 1 });
+        let shorter = ParsedText { verses: vec![verse("t", 19, 23, 1, "The LORD is my shepherd")], book_names: HashMap::new() };
+        let store = write_store("rewrite.store", &shorter, &SourceStamp { len: 2, modified_ms: 2 });
+        assert_eq!(store.chapters(), [(19, 23)]);
+        assert_eq!(store.source(), &SourceStamp { len: 2, modified_ms: 2 });
+        let _ = std::fs::remove_file(scratch_path("rewrite.store"));
+    }
+
+    #[test]
+    fn older_layouts_are_not_opened() {
+        let path = scratch_path("v1.store");
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 20]);
+        std::fs::write(&path, bytes).unwrap();
+        assert!(matches!(ChapterStore::open(&path), Err(BibleError::ParseError { .. })));
+
+        std::fs::write(&path, b"not a store").unwrap();
+        assert!(ChapterStore::open(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn lengths_past_the_end_of_the_file_are_corrupt() {
+        let path = scratch_path("corrupt.store");
+        let psalm = ParsedText {
+            verses: vec![verse("t", 19, 23, 1, "The LORD is my shepherd")],
+            book_names: HashMap::from([(19, "Psalms".to_string())]),
+        };
+        write_store("corrupt.store", &psalm, &SourceStamp { len: 1, modified_ms: 1 });
+        let good = std::fs::read(&path).unwrap();
+
+        // The first book's name length follows magic, version, stamp, book count, id and chapter count
+        let mut bytes = good.clone();
+        bytes[36..40].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        assert!(matches!(ChapterStore::open(&path), Err(BibleError::Corrupt { .. })));
+
+        // The only verse's text length sits just before its text, at the end of the file
+        let mut bytes = good.clone();
+        let at = bytes.len() - "The LORD is my shepherd".len() - 4;
+        bytes[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        let store = ChapterStore::open(&path).unwrap();
+        assert!(matches!(store.read_chapter("t", 19, 23), Err(BibleError::Corrupt { .. })));
+
+        std::fs::write(&path, &good[..good.len() - 5]).unwrap();
+        let store = ChapterStore::open(&path).unwrap();
+        assert!(matches!(store.all_verses("t"), Err(BibleError::Corrupt { .. })));
+        let _ = std::fs::remove_file(&path);
+    }
+}
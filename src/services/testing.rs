@@ -0,0 +1,47 @@
+//! Fixtures shared by the service tests
+
+use super::formats::ParsedText;
+use super::store::ChapterStore;
+use super::SourceStamp;
+use crate::types::{Translation, Verse, Versification};
+use std::path::PathBuf;
+
+/// An English, KJV-numbered translation; override fields with `..translation(id)`
+pub fn translation(id: &str) -> Translation {
+    Translation {
+        id: id.to_string(),
+        name: id.to_uppercase(),
+        abbreviation: id.to_uppercase(),
+        language: "en".into(),
+        language_name: None,
+        description: id.to_string(),
+        bundled: false,
+        priority: 0,
+        versification: Versification::default(),
+        coverage: None,
+    }
+}
+
+pub fn verse(translation_id: &str, book_id: u32, chapter: u32, verse: u32, text: &str) -> Verse {
+    Verse {
+        id: format!("{}:{}:{}:{}", translation_id, book_id, chapter, verse),
+        translation_id: translation_id.to_string(),
+        book_id,
+        chapter,
+        verse,
+        text: text.to_string(),
+    }
+}
+
+/// A path in the temp directory no other test or test run uses; nothing is created there
+pub fn scratch_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("studybible-test-{}-{}", std::process::id(), name))
+}
+
+
+/// A chapter store holding `text`, written to `scratch_path(name)` and opened
+pub fn write_store(name: &str, text: &ParsedText, source: &SourceStamp) -> ChapterStore {
+    let path = scratch_path(name);
+    ChapterStore::write(&path, text, source).unwrap();
+    ChapterStore::open(&path).unwrap()
+}
@@ -0,0 +1,166 @@
+//! Integrity checks for translation files: size and checksum against what the index lists,
+//! then a sample of verses read back from the chapter store.
+
+use super::download::RemoteFile;
+use super::store::ChapterStore;
+use super::SourceStamp;
+use crate::error::BibleError;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Chapters read back when checking a store, spread evenly over the whole text
+const SAMPLE_CHAPTERS: usize = 5;
+
+/// A check of one translation's files, taken from `BibleService::pending_checks` so it can run
+/// while the service is unlocked
+#[derive(Debug, Clone)]
+pub struct DownloadCheck {
+    pub translation_id: String,
+    path: PathBuf,
+    remote: Option<RemoteFile>,
+}
+
+impl DownloadCheck {
+    pub(super) fn new(translation_id: String, path: PathBuf, remote: Option<RemoteFile>) -> Self {
+        Self { translation_id, path, remote }
+    }
+
+    /// Run the check on the blocking pool, since hashing and importing a large file take a while
+    pub async fn run(self) -> (String, Result<(), BibleError>) {
+        let Self { translation_id, path, remote } = self;
+        let id = translation_id.clone();
+        let result = tokio::task::spawn_blocking(move || super::check_translation(&id, remote.as_ref()))
+            .await
+            .unwrap_or_else(|e| Err(BibleError::io("verify", &path, e)));
+        (translation_id, result)
+    }
+}
+
+/// Compare a downloaded file with the size and checksum its index entry lists
+pub fn check_file(path: &Path, remote: &RemoteFile) -> Result<(), BibleError> {
+    let corrupt = |reason: String| BibleError::Corrupt { path: path.display().to_string(), reason };
+
+    let len = std::fs::metadata(path).map_err(|e| BibleError::io("stat", path, e))?.len();
+    if let Some(expected) = remote.size {
+        if len != expected {
+            return Err(corrupt(format!("{} bytes on disk, index lists {}", len, expected)));
+        }
+    }
+    if let Some(expected) = &remote.sha256 {
+        let actual = sha256_hex(path)?;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(corrupt(format!("checksum {} does not match {}", actual, expected)));
+        }
+    }
+    Ok(())
+}
+
+/// Read a handful of chapters back from the store and make sure they hold text
+pub fn check_sample(store: &ChapterStore, translation_id: &str, path: &Path) -> Result<(), BibleError> {
+    let corrupt = |reason: &str| BibleError::Corrupt { path: path.display().to_string(), reason: reason.to_string() };
+
+    let chapters = store.chapters();
+    if chapters.is_empty() {
+        return Err(corrupt("no chapters found"));
+    }
+    let step = (chapters.len() / SAMPLE_CHAPTERS).max(1);
+    for (book_id, chapter) in chapters.iter().step_by(step).chain(chapters.last()) {
+        let verses = store.read_chapter(translation_id, *book_id, *chapter)?;
+        if verses.is_empty() || verses.iter().all(|v| v.text.trim().is_empty()) {
+            return Err(corrupt(&format!("book {} chapter {} has no text", book_id, chapter)));
+        }
+    }
+    Ok(())
+}
+
+fn sha256_hex(path: &Path) -> Result<String, BibleError> {
+    let mut reader = BufReader::new(File::open(path).map_err(|e| BibleError::io("open", path, e))?);
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| BibleError::io("read", path, e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Sources that passed verification, by translation id, so unchanged files aren't rehashed on every start
+pub fn read_verified(path: &Path) -> HashMap<String, SourceStamp> {
+    std::fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_verified(path: &Path, verified: &HashMap<String, SourceStamp>) -> Result<(), BibleError> {
+    let bytes = serde_json::to_vec_pretty(verified).map_err(|e| BibleError::serialize(path, e))?;
+    std::fs::write(path, bytes).map_err(|e| BibleError::io("write", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::formats::ParsedText;
+    use super::super::testing::{scratch_path, verse, write_store};
+    use super::*;
+
+    fn remote(size: Option<u64>, sha256: Option<String>) -> RemoteFile {
+        RemoteFile { url: "https://example.org/t.xml".into(), mirrors: Vec::new(), size, sha256, year: None, status: None }
+    }
+
+    /// A store at `scratch_path(name)` with one verse per (book, chapter, text)
+    fn store(name: &str, texts: &[(u32, u32, &str)]) -> ChapterStore {
+        let verses = texts.iter().map(|&(book_id, chapter, text)| verse("t", book_id, chapter, 1, text)).collect();
+        write_store(name, &ParsedText { verses, book_names: HashMap::new() }, &SourceStamp { len: 0, modified_ms: 0 })
+    }
+
+    #[test]
+    fn file_must_match_listed_size_and_checksum() {
+        let path = scratch_path("verify-file.xml");
+        std::fs::write(&path, b"<bible/>").unwrap();
+        let sha = hex(&Sha256::digest(b"<bible/>"));
+
+        assert!(check_file(&path, &remote(None, None)).is_ok());
+        assert!(check_file(&path, &remote(Some(8), Some(sha.to_uppercase()))).is_ok());
+        assert!(matches!(check_file(&path, &remote(Some(9), None)), Err(BibleError::Corrupt { .. })));
+        let other = hex(&Sha256::digest(b"other"));
+        assert!(matches!(check_file(&path, &remote(None, Some(other))), Err(BibleError::Corrupt { .. })));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn sample_needs_text_in_every_chapter_read() {
+        let texts: Vec<(u32, u32, &str)> = (1..=20).map(|c| (1, c, "text")).collect();
+        let path = scratch_path("verify-sample.store");
+        assert!(check_sample(&store("verify-sample.store", &texts), "t", &path).is_ok());
+
+        // The last chapter is always read
+        let mut blank_last = texts.clone();
+        blank_last.push((2, 1, "  "));
+        let bad = store("verify-sample.store", &blank_last);
+        assert!(matches!(check_sample(&bad, "t", &path), Err(BibleError::Corrupt { .. })));
+
+        assert!(check_sample(&store("verify-sample.store", &[]), "t", &path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn verified_stamps_round_trip() {
+        let path = scratch_path("verified.json");
+        let _ = std::fs::remove_file(&path);
+        assert!(read_verified(&path).is_empty());
+        let verified = HashMap::from([("kjv".to_string(), SourceStamp { len: 1, modified_ms: 2 })]);
+        write_verified(&path, &verified).unwrap();
+        assert_eq!(read_verified(&path), verified);
+        let _ = std::fs::remove_file(&path);
+    }
+}
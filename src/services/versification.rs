@@ -0,0 +1,254 @@
+//! Verse numbers across versification schemes. Each scheme maps to and from KJV numbering, so
+//! any two translations line up through it. Books other than those listed number alike in every
+//! scheme.
+
+use crate::types::Versification;
+
+/// Open end of a verse span: to the end of the chapter
+const END: u32 = u32::MAX;
+
+/// Hebrew spans numbered differently in KJV, as (book, chapter, first verse, last verse, KJV
+/// chapter, KJV first verse). Each maps verse for verse, so it also reads backwards.
+const HEBREW_SHIFTS: &[(u32, u32, u32, u32, u32, u32)] = &[
+    (1, 32, 1, 1, 31, 55), (1, 32, 2, END, 32, 1),
+    (2, 7, 26, 29, 8, 1), (2, 8, 1, END, 8, 5),
+    (2, 21, 37, 37, 22, 1), (2, 22, 1, END, 22, 2),
+    (3, 5, 20, 26, 6, 1), (3, 6, 1, END, 6, 8),
+    (4, 17, 1, 15, 16, 36), (4, 17, 16, END, 17, 1),
+    (4, 30, 1, 1, 29, 40), (4, 30, 2, END, 30, 1),
+    (5, 13, 1, 1, 12, 32), (5, 13, 2, END, 13, 1),
+    (5, 23, 1, 1, 22, 30), (5, 23, 2, END, 23, 1),
+    (9, 21, 2, END, 21, 1),
+    (9, 24, 1, 1, 23, 29), (9, 24, 2, END, 24, 1),
+    (10, 19, 1, 1, 18, 33), (10, 19, 2, END, 19, 1),
+    (11, 5, 1, 14, 4, 21), (11, 5, 15, END, 5, 1),
+    (12, 12, 1, 1, 11, 21), (12, 12, 2, END, 12, 1),
+    (13, 5, 27, 41, 6, 1), (13, 6, 1, END, 6, 16),
+    (16, 3, 33, 38, 4, 1), (16, 4, 1, END, 4, 7),
+    (18, 40, 25, 32, 41, 1), (18, 41, 1, END, 41, 9),
+    (21, 4, 17, 17, 5, 1), (21, 5, 1, END, 5, 2),
+    (22, 7, 1, 1, 6, 13), (22, 7, 2, END, 7, 1),
+    (23, 8, 23, 23, 9, 1), (23, 9, 1, END, 9, 2),
+    (23, 64, 1, END, 64, 2),
+    (24, 8, 23, 23, 9, 1), (24, 9, 1, END, 9, 2),
+    (26, 21, 1, 5, 20, 45), (26, 21, 6, END, 21, 1),
+    (27, 3, 31, 33, 4, 1), (27, 4, 1, END, 4, 4),
+    (27, 6, 1, 1, 5, 31), (27, 6, 2, END, 6, 1),
+    (28, 2, 1, 2, 1, 10), (28, 2, 3, END, 2, 1),
+    (28, 12, 1, 1, 11, 12), (28, 12, 2, END, 12, 1),
+    (28, 14, 1, 1, 13, 16), (28, 14, 2, END, 14, 1),
+    (29, 3, 1, 5, 2, 28), (29, 4, 1, END, 3, 1),
+    (32, 2, 1, 1, 1, 17), (32, 2, 2, END, 2, 1),
+    (33, 4, 14, 14, 5, 1), (33, 5, 1, END, 5, 2),
+    (34, 2, 1, 1, 1, 15), (34, 2, 2, END, 2, 1),
+    (38, 2, 1, 4, 1, 18), (38, 2, 5, END, 2, 1),
+    (39, 3, 19, END, 4, 1),
+];
+
+/// Hebrew verses that KJV joins to a neighbour, as (book, chapter, verse, KJV chapter, KJV verse).
+/// These only read forwards: the KJV verse maps back to the rest of its text.
+const HEBREW_JOINED: &[(u32, u32, u32, u32, u32)] = &[(9, 21, 1, 20, 42)];
+
+/// Psalms whose title is verse 1 in Hebrew numbering and unnumbered in KJV
+const ONE_VERSE_TITLES: &[u32] = &[
+    3, 4, 5, 6, 7, 8, 9, 12, 13, 18, 19, 20, 21, 22, 30, 31, 34, 36, 38, 39, 40, 41, 42, 44, 45, 46, 47, 48,
+    49, 53, 55, 56, 57, 58, 59, 61, 62, 63, 64, 65, 67, 68, 69, 70, 75, 76, 77, 80, 81, 83, 84, 85, 88, 89,
+    92, 102, 108, 140, 142,
+];
+
+/// Psalms whose title takes verses 1 and 2 in Hebrew numbering
+const TWO_VERSE_TITLES: &[u32] = &[51, 52, 54, 60];
+
+const PSALMS: u32 = 19;
+
+/// `chapter:verse` of a book as numbered in `from`, numbered as in `to`. Verses one scheme joins
+/// that the other keeps apart map onto the joined verse.
+pub fn map_verse(from: Versification, to: Versification, book_id: u32, chapter: u32, verse: u32) -> (u32, u32) {
+    if from == to {
+        return (chapter, verse);
+    }
+    let (chapter, verse) = to_kjv(from, book_id, chapter, verse);
+    from_kjv(to, book_id, chapter, verse)
+}
+
+fn to_kjv(scheme: Versification, book_id: u32, chapter: u32, verse: u32) -> (u32, u32) {
+    match scheme {
+        Versification::Kjv => (chapter, verse),
+        Versification::Hebrew => hebrew_to_kjv(book_id, chapter, verse),
+        Versification::Vulgate if book_id == PSALMS => {
+            let (chapter, verse) = vulgate_to_hebrew_psalm(chapter, verse);
+            hebrew_to_kjv(book_id, chapter, verse)
+        }
+        Versification::Vulgate => (chapter, verse),
+    }
+}
+
+fn from_kjv(scheme: Versification, book_id: u32, chapter: u32, verse: u32) -> (u32, u32) {
+    match scheme {
+        Versification::Kjv => (chapter, verse),
+        Versification::Hebrew => kjv_to_hebrew(book_id, chapter, verse),
+        Versification::Vulgate if book_id == PSALMS => {
+            let (chapter, verse) = kjv_to_hebrew(book_id, chapter, verse);
+            hebrew_to_vulgate_psalm(chapter, verse)
+        }
+        Versification::Vulgate => (chapter, verse),
+    }
+}
+
+fn hebrew_to_kjv(book_id: u32, chapter: u32, verse: u32) -> (u32, u32) {
+    if book_id == PSALMS {
+        let titles = psalm_title_verses(chapter);
+        return (chapter, verse.saturating_sub(titles).max(1));
+    }
+    if let Some(&(_, _, _, to_chapter, to_verse)) =
+        HEBREW_JOINED.iter().find(|&&(b, c, v, _, _)| (b, c, v) == (book_id, chapter, verse))
+    {
+        return (to_chapter, to_verse);
+    }
+    HEBREW_SHIFTS
+        .iter()
+        .find(|&&(b, c, first, last, _, _)| b == book_id && c == chapter && (first..=last).contains(&verse))
+        .map_or((chapter, verse), |&(_, _, first, _, to_chapter, to_first)| (to_chapter, to_first + (verse - first)))
+}
+
+fn kjv_to_hebrew(book_id: u32, chapter: u32, verse: u32) -> (u32, u32) {
+    if book_id == PSALMS {
+        return (chapter, verse + psalm_title_verses(chapter));
+    }
+    HEBREW_SHIFTS
+        .iter()
+        .find(|&&(b, _, first, last, to_chapter, to_first)| {
+            let to_last = if last == END { END } else { to_first + (last - first) };
+            b == book_id && to_chapter == chapter && (to_first..=to_last).contains(&verse)
+        })
+        .map_or((chapter, verse), |&(_, c, first, _, _, to_first)| (c, first + (verse - to_first)))
+}
+
+/// Verses the Hebrew numbering gives a psalm's title
+fn psalm_title_verses(psalm: u32) -> u32 {
+    if TWO_VERSE_TITLES.contains(&psalm) {
+        2
+    } else if ONE_VERSE_TITLES.contains(&psalm) {
+        1
+    } else {
+        0
+    }
+}
+
+/// The Greek and Latin Psalter joins Psalms 9-10 and 114-115, and splits 116 and 147
+fn vulgate_to_hebrew_psalm(psalm: u32, verse: u32) -> (u32, u32) {
+    match psalm {
+        9 if verse > 21 => (10, verse - 21),
+        10..=112 | 116..=145 => (psalm + 1, verse),
+        113 if verse > 8 => (115, verse - 8),
+        113 => (114, verse),
+        114 => (116, verse),
+        115 => (116, verse + 9),
+        146 => (147, verse),
+        147 => (147, verse + 11),
+        _ => (psalm, verse),
+    }
+}
+
+fn hebrew_to_vulgate_psalm(psalm: u32, verse: u32) -> (u32, u32) {
+    match psalm {
+        10 => (9, verse + 21),
+        11..=113 | 117..=146 => (psalm - 1, verse),
+        114 => (113, verse),
+        115 => (113, verse + 8),
+        116 if verse > 9 => (115, verse - 9),
+        116 => (114, verse),
+        147 if verse > 11 => (147, verse - 11),
+        147 => (146, verse),
+        _ => (psalm, verse),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Versification::{Hebrew, Kjv, Vulgate};
+
+    /// `a` in scheme `from` is `b` in `to`, and back
+    fn same_verse(book_id: u32, from: Versification, a: (u32, u32), to: Versification, b: (u32, u32)) {
+        assert_eq!(map_verse(from, to, book_id, a.0, a.1), b, "{:?} {:?} -> {:?}", from, a, to);
+        assert_eq!(map_verse(to, from, book_id, b.0, b.1), a, "{:?} {:?} -> {:?}", to, b, from);
+    }
+
+    #[test]
+    fn same_scheme_is_unchanged() {
+        for scheme in [Kjv, Hebrew, Vulgate] {
+            assert_eq!(map_verse(scheme, scheme, PSALMS, 9, 22), (9, 22));
+        }
+    }
+
+    #[test]
+    fn malachi_4_is_hebrew_malachi_3() {
+        same_verse(39, Hebrew, (3, 18), Kjv, (3, 18));
+        same_verse(39, Hebrew, (3, 19), Kjv, (4, 1));
+        same_verse(39, Hebrew, (3, 24), Kjv, (4, 6));
+    }
+
+    #[test]
+    fn hebrew_joel_has_four_chapters() {
+        same_verse(29, Hebrew, (2, 27), Kjv, (2, 27));
+        same_verse(29, Hebrew, (3, 1), Kjv, (2, 28));
+        same_verse(29, Hebrew, (3, 5), Kjv, (2, 32));
+        same_verse(29, Hebrew, (4, 1), Kjv, (3, 1));
+        same_verse(29, Hebrew, (4, 21), Kjv, (3, 21));
+    }
+
+    #[test]
+    fn psalm_titles_are_verses_in_hebrew() {
+        // The title joins verse 1 in KJV, which maps back to the first verse after the title
+        assert_eq!(map_verse(Hebrew, Kjv, PSALMS, 3, 1), (3, 1));
+        same_verse(PSALMS, Hebrew, (3, 2), Kjv, (3, 1));
+        same_verse(PSALMS, Hebrew, (3, 9), Kjv, (3, 8));
+        same_verse(PSALMS, Hebrew, (51, 3), Kjv, (51, 1));
+        // No title of its own
+        same_verse(PSALMS, Hebrew, (23, 1), Kjv, (23, 1));
+    }
+
+    #[test]
+    fn genesis_31_55_is_hebrew_32_1() {
+        same_verse(1, Hebrew, (31, 54), Kjv, (31, 54));
+        same_verse(1, Hebrew, (32, 1), Kjv, (31, 55));
+        same_verse(1, Hebrew, (32, 2), Kjv, (32, 1));
+        same_verse(1, Hebrew, (32, 33), Kjv, (32, 32));
+    }
+
+    #[test]
+    fn vulgate_joins_psalms_9_and_10() {
+        same_verse(PSALMS, Vulgate, (9, 2), Kjv, (9, 1));
+        same_verse(PSALMS, Vulgate, (9, 21), Kjv, (9, 20));
+        same_verse(PSALMS, Vulgate, (9, 22), Kjv, (10, 1));
+        same_verse(PSALMS, Vulgate, (9, 39), Kjv, (10, 18));
+        same_verse(PSALMS, Vulgate, (10, 1), Kjv, (11, 1));
+        same_verse(PSALMS, Vulgate, (22, 1), Kjv, (23, 1));
+    }
+
+    #[test]
+    fn vulgate_joins_114_115_and_splits_116() {
+        same_verse(PSALMS, Vulgate, (113, 1), Kjv, (114, 1));
+        same_verse(PSALMS, Vulgate, (113, 8), Kjv, (114, 8));
+        same_verse(PSALMS, Vulgate, (113, 9), Kjv, (115, 1));
+        same_verse(PSALMS, Vulgate, (114, 1), Kjv, (116, 1));
+        same_verse(PSALMS, Vulgate, (115, 1), Kjv, (116, 10));
+        same_verse(PSALMS, Vulgate, (116, 1), Kjv, (117, 1));
+        same_verse(PSALMS, Vulgate, (146, 1), Kjv, (147, 1));
+        same_verse(PSALMS, Vulgate, (147, 1), Kjv, (147, 12));
+        // Hebrew and Vulgate line up through KJV
+        same_verse(PSALMS, Vulgate, (113, 9), Hebrew, (115, 1));
+    }
+
+    #[test]
+    fn shifted_spans_read_both_ways() {
+        for &(book, chapter, first, last, _, _) in HEBREW_SHIFTS {
+            let last = if last == END { first + 3 } else { last };
+            for verse in first..=last {
+                let (c, v) = map_verse(Hebrew, Kjv, book, chapter, verse);
+                assert_eq!(map_verse(Kjv, Hebrew, book, c, v), (chapter, verse), "{} {}:{}", book, chapter, verse);
+            }
+        }
+    }
+}
@@ -13,6 +13,36 @@ pub struct Translation {
     pub bundled: bool,
     #[serde(default)]
     pub priority: u32,
+    /// How chapters and verses are numbered, for lining the text up with other translations
+    #[serde(default)]
+    pub versification: Versification,
+    /// Which testaments the translation has text for; `None` when not known
+    #[serde(default)]
+    pub coverage: Option<TestamentCoverage>,
+}
+
+/// Which testaments a translation includes, e.g. only the New Testament
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestamentCoverage {
+    pub old_testament: bool,
+    pub new_testament: bool,
+}
+
+impl TestamentCoverage {
+    /// Coverage of a book list: a testament is included when any of its books is
+    pub fn of(books: &[Book]) -> Self {
+        Self {
+            old_testament: books.iter().any(|b| b.testament == Testament::OT),
+            new_testament: books.iter().any(|b| b.testament == Testament::NT),
+        }
+    }
+
+    pub fn includes(&self, testament: &Testament) -> bool {
+        match testament {
+            Testament::OT => self.old_testament,
+            Testament::NT => self.new_testament,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -23,6 +53,18 @@ pub struct Book {
     pub testament: Testament,
     pub order_index: u32,
     pub chapter_count: u32,
+    /// Name in the translation's language, when we have one (e.g. ஆதியாகமம் for Tamil)
+    #[serde(default)]
+    pub localized_name: Option<String>,
+    #[serde(default)]
+    pub canon: Canon,
+}
+
+impl Book {
+    /// The name to show: localized if available, English otherwise
+    pub fn display_name(&self) -> &str {
+        self.localized_name.as_deref().unwrap_or(&self.name)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -63,6 +105,15 @@ pub struct BibleReference {
     pub verse: Option<u32>,
 }
 
+/// An inclusive span of verses, possibly across chapters. A reference without a verse stands for
+/// its whole chapter: at the start of a range it begins at verse 1, at the end it runs to the
+/// chapter's last verse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerseRange {
+    pub start: BibleReference,
+    pub end: BibleReference,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchResult {
     pub verses: Vec<VerseWithBook>,
@@ -112,6 +163,39 @@ pub enum Testament {
     NT, // New Testament
 }
 
+/// Which Bibles include a book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Canon {
+    /// Every Bible: the 39 Old Testament books and the New Testament
+    #[default]
+    Protocanon,
+    /// Catholic and Orthodox Bibles: Tobit, Judith, Wisdom, Sirach, Baruch, 1-2 Maccabees and
+    /// the Greek parts of Esther and Daniel
+    Deuterocanon,
+    /// Orthodox Bibles only, e.g. 1 Esdras, 3 Maccabees and Psalm 151
+    Orthodox,
+    /// Printed in some Bibles or appendices but in none of these canons, e.g. 2 Esdras
+    Other,
+}
+
+/// Chapter and verse numbering schemes. They differ mostly in the Old Testament, e.g. Malachi 4
+/// in English Bibles is Malachi 3:19-24 in Hebrew ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Versification {
+    /// English Bibles since the KJV; the others are mapped through this one
+    #[default]
+    Kjv,
+    /// Masoretic numbering: Psalm titles counted as verses, Joel in 4 chapters, Malachi in 3
+    #[serde(alias = "masoretic")]
+    Hebrew,
+    /// Septuagint and Vulgate Psalter: Psalms 9-147 mostly numbered one lower (9 and 10 are one
+    /// psalm) and titles counted as verses; other books as in KJV
+    #[serde(alias = "lxx", alias = "septuagint")]
+    Vulgate,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum VerseDisplayMode {